@@ -563,8 +563,8 @@ mod tests {
         let res = machine.verify_transaction_basic(&transaction, &header);
         assert_eq!(
             res,
-            Err(transaction::Error::InvalidSignature(
-                "Crypto error (Invalid EC signature)".into()
+            Err(transaction::Error::Rejected(
+                transaction::RejectionReason::Unsigned
             ))
         );
     }
@@ -17,7 +17,7 @@
 //! Ethereum transaction
 
 use evm::Schedule;
-use types::transaction::{self, Action};
+use types::transaction::{self, GasSchedule};
 
 /// Extends transaction with gas verification method.
 pub trait Transaction {
@@ -27,30 +27,94 @@ pub trait Transaction {
 
 impl Transaction for transaction::Transaction {
     fn gas_required(&self, schedule: &Schedule) -> u64 {
-        gas_required_for(
-            match self.action {
-                Action::Create => true,
-                Action::Call(_) => false,
-            },
-            &self.data,
-            schedule,
-        )
+        self.intrinsic_gas(&gas_schedule(schedule))
     }
 }
 
-/// Get the transaction cost in gas for the given params.
-fn gas_required_for(is_create: bool, data: &[u8], schedule: &Schedule) -> u64 {
-    data.iter().fold(
-        (if is_create {
-            schedule.tx_create_gas
-        } else {
-            schedule.tx_gas
-        }) as u64,
-        |g, b| {
-            g + (match *b {
-                0 => schedule.tx_data_zero_gas,
-                _ => schedule.tx_data_non_zero_gas,
-            }) as u64
-        },
-    )
+/// Get the transaction gas costs of the given schedule.
+///
+/// Panics if `tx_create_gas` is lower than `tx_gas`, as contract creation always includes
+/// the base cost of a transaction.
+pub fn gas_schedule(schedule: &Schedule) -> GasSchedule {
+    assert!(
+        schedule.tx_create_gas >= schedule.tx_gas,
+        "tx_create_gas is never below tx_gas in any schedule; qed"
+    );
+    GasSchedule {
+        base_gas: schedule.tx_gas as u64,
+        create_gas: (schedule.tx_create_gas - schedule.tx_gas) as u64,
+        zero_byte_gas: schedule.tx_data_zero_gas as u64,
+        non_zero_byte_gas: schedule.tx_data_non_zero_gas as u64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::transaction::Action;
+
+    #[test]
+    fn gas_schedule_presets_match_vm_schedule() {
+        assert_eq!(
+            gas_schedule(&Schedule::new_frontier()),
+            GasSchedule::frontier()
+        );
+        assert_eq!(
+            gas_schedule(&Schedule::new_homestead()),
+            GasSchedule::homestead()
+        );
+        assert_eq!(
+            gas_schedule(&Schedule::new_istanbul()),
+            GasSchedule::istanbul()
+        );
+    }
+
+    #[test]
+    fn gas_required_matches_intrinsic_gas() {
+        let schedule = Schedule::new_istanbul();
+        let create = transaction::Transaction {
+            action: Action::Create,
+            data: vec![0x60, 0x00, 0x60, 0x00, 0xf3],
+            ..Default::default()
+        };
+        let call = transaction::Transaction {
+            action: Action::Call(0x1234.into()),
+            data: vec![0x00, 0x01],
+            ..Default::default()
+        };
+
+        assert_eq!(create.gas_required(&schedule), 53000 + 2 * 4 + 3 * 16);
+        assert_eq!(call.gas_required(&schedule), 21000 + 4 + 16);
+        for tx in &[create, call] {
+            assert_eq!(
+                tx.gas_required(&schedule),
+                tx.intrinsic_gas(&GasSchedule::istanbul())
+            );
+        }
+    }
+
+    #[test]
+    fn gas_breakdown_sums_to_gas_required() {
+        let schedule = Schedule::new_istanbul();
+        let create = transaction::Transaction {
+            action: Action::Create,
+            data: vec![0x60, 0x00, 0x60, 0x00, 0xf3],
+            ..Default::default()
+        };
+        let breakdown = create.gas_breakdown(&gas_schedule(&schedule));
+
+        assert_eq!(
+            breakdown.base + breakdown.create,
+            schedule.tx_create_gas as u64
+        );
+        assert_eq!(breakdown.total(), create.gas_required(&schedule));
+    }
+
+    #[test]
+    #[should_panic]
+    fn gas_schedule_rejects_create_gas_below_base_gas() {
+        let mut schedule = Schedule::new_homestead();
+        schedule.tx_create_gas = schedule.tx_gas - 1;
+        gas_schedule(&schedule);
+    }
 }
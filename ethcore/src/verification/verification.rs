@@ -513,7 +513,9 @@ mod tests {
     use types::{
         encoded,
         log_entry::{LocalizedLogEntry, LogEntry},
-        transaction::{Action, SignedTransaction, Transaction, UnverifiedTransaction},
+        transaction::{
+            self, Action, RejectionReason, SignedTransaction, Transaction, UnverifiedTransaction,
+        },
     };
 
     fn check_ok(result: Result<(), Error>) {
@@ -850,7 +852,7 @@ mod tests {
         bad_header.set_transactions_root(eip86_transactions_root.clone());
         bad_header.set_uncles_hash(good_uncles_hash.clone());
         match basic_test(&create_test_block_with_data(&bad_header, &eip86_transactions, &good_uncles), engine) {
-			Err(Error(ErrorKind::Transaction(ref e), _)) if e == &::ethkey::Error::InvalidSignature.into() => (),
+			Err(Error(ErrorKind::Transaction(ref e), _)) if e == &transaction::Error::Rejected(RejectionReason::Unsigned) => (),
 			e => panic!("Block verification failed.\nExpected: Transaction Error (Invalid Signature)\nGot: {:?}", e),
 		}
 
@@ -41,14 +41,12 @@ extern crate heapsize;
 extern crate keccak_hash as hash;
 extern crate parity_bytes as bytes;
 extern crate rlp;
+extern crate rustc_hex;
 extern crate unexpected;
 
 #[macro_use]
 extern crate rlp_derive;
 
-#[cfg(test)]
-extern crate rustc_hex;
-
 #[macro_use]
 pub mod views;
 
@@ -16,7 +16,7 @@
 
 use std::{error, fmt};
 
-use ethereum_types::U256;
+use ethereum_types::{H256, U256};
 use ethkey;
 use rlp;
 use unexpected::OutOfBounds;
@@ -28,6 +28,15 @@ pub enum Error {
     AlreadyImported,
     /// Transaction is not valid anymore (state already has higher nonce)
     Old,
+    /// Transaction nonce is too far ahead of the sender's current nonce
+    NonceTooFarInFuture {
+        /// Highest accepted nonce
+        limit: U256,
+        /// Transaction nonce
+        got: U256,
+    },
+    /// Transaction nonce does not fit in 64 bits
+    NonceOverflow(U256),
     /// Transaction was not imported to the queue because limit has been reached.
     LimitReached,
     /// Transaction's gas price is below threshold.
@@ -66,10 +75,28 @@ pub enum Error {
         /// Declared transaction gas
         got: U256,
     },
+    /// Transaction's gas is higher than the per-transaction gas cap
+    TxGasCapExceeded {
+        /// Declared transaction gas
+        got: U256,
+        /// Per-transaction gas cap
+        cap: U256,
+    },
+    /// Transaction's gas price is above the configured ceiling
+    GasPriceTooHigh {
+        /// Maximal allowed gas price
+        limit: U256,
+        /// Transaction gas price
+        got: U256,
+    },
+    /// Transaction's gas limit (aka gas) is zero, so it can never execute.
+    ZeroGasLimit,
     /// Transaction's gas limit (aka gas) is invalid.
     InvalidGasLimit(OutOfBounds<U256>),
     /// Transaction sender is banned.
     SenderBanned,
+    /// Transaction sender has code deployed (EIP-3607).
+    SenderIsContract,
     /// Transaction receipient is banned.
     RecipientBanned,
     /// Contract creation code is banned.
@@ -84,6 +111,55 @@ pub enum Error {
     TooBig,
     /// Invalid RLP encoding
     InvalidRlp(String),
+    /// Signature was produced for a different hash than the transaction's signing hash
+    SigningHashMismatch {
+        /// Hash the signature was produced for
+        expected: H256,
+        /// Signing hash computed from the transaction
+        computed: H256,
+    },
+    /// Transaction failed basic verification of its signature
+    Rejected(RejectionReason),
+}
+
+/// Reason for rejecting a transaction in basic verification.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum RejectionReason {
+    /// Transaction has an empty signature
+    Unsigned,
+    /// Signature `r` or `s` is zero or not lower than the curve order
+    SignatureOutOfRange,
+    /// Signature has a high `s` value
+    HighS,
+    /// Transaction is signed for a different chain
+    InvalidChainId,
+}
+
+impl From<RejectionReason> for Error {
+    fn from(reason: RejectionReason) -> Self {
+        match reason {
+            RejectionReason::InvalidChainId => Error::InvalidChainId,
+            reason => Error::Rejected(reason),
+        }
+    }
+}
+
+impl RejectionReason {
+    fn message(&self) -> &'static str {
+        use self::RejectionReason::*;
+        match *self {
+            Unsigned => "Transaction is not signed",
+            SignatureOutOfRange => "Signature values are out of range",
+            HighS => "Signature has a high s value",
+            InvalidChainId => "Transaction of this chain ID is not allowed on this chain",
+        }
+    }
+}
+
+impl fmt::Display for RejectionReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_fmt(format_args!("Transaction rejected ({})", self.message()))
+    }
 }
 
 impl From<ethkey::Error> for Error {
@@ -108,6 +184,13 @@ impl fmt::Display for Error {
                 "Gas price too low to replace, previous tx gas: {:?}, new tx gas: {:?}",
                 prev, new
             ),
+            NonceTooFarInFuture { limit, got } => {
+                format!(
+                    "Nonce too far in the future. Limit={}, Given={}",
+                    limit, got
+                )
+            }
+            NonceOverflow(nonce) => format!("Nonce does not fit in 64 bits. Given={}", nonce),
             LimitReached => "Transaction limit reached".into(),
             InsufficientGasPrice { minimal, got } => {
                 format!("Insufficient gas price. Min={}, Given={}", minimal, got)
@@ -122,8 +205,16 @@ impl fmt::Display for Error {
             GasLimitExceeded { limit, got } => {
                 format!("Gas limit exceeded. Limit={}, Given={}", limit, got)
             }
+            TxGasCapExceeded { got, cap } => {
+                format!("Transaction gas cap exceeded. Cap={}, Given={}", cap, got)
+            }
+            GasPriceTooHigh { limit, got } => {
+                format!("Gas price too high. Limit={}, Given={}", limit, got)
+            }
+            ZeroGasLimit => "Zero gas limit.".into(),
             InvalidGasLimit(ref err) => format!("Invalid gas limit. {}", err),
             SenderBanned => "Sender is temporarily banned.".into(),
+            SenderIsContract => "Sender is a contract.".into(),
             RecipientBanned => "Recipient is temporarily banned.".into(),
             CodeBanned => "Contract code is temporarily banned.".into(),
             InvalidChainId => "Transaction of this chain ID is not allowed on this chain.".into(),
@@ -133,6 +224,11 @@ impl fmt::Display for Error {
             }
             TooBig => "Transaction too big".into(),
             InvalidRlp(ref err) => format!("Transaction has invalid RLP structure: {}.", err),
+            SigningHashMismatch { expected, computed } => format!(
+                "Signing hash mismatch. Expected={:?}, Computed={:?}",
+                expected, computed
+            ),
+            Rejected(ref reason) => format!("{}.", reason.message()),
         };
 
         f.write_fmt(format_args!("Transaction error ({})", msg))
@@ -17,6 +17,10 @@
 //! Ethereum Transactions
 
 mod error;
+mod proto;
 mod transaction;
 
-pub use self::{error::Error, transaction::*};
+pub use self::{
+    error::{Error, RejectionReason},
+    transaction::*,
+};
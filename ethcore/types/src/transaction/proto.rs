@@ -0,0 +1,224 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of OpenEthereum.
+
+// OpenEthereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// OpenEthereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with OpenEthereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Protobuf encoding of transactions, for services without an RLP codec:
+//!
+//! ```text
+//! message Transaction {
+//!   uint64 version = 1;
+//!   bytes nonce = 2;     // big-endian, leading zeros stripped, as are all numbers
+//!   bytes gas_price = 3;
+//!   bytes gas = 4;
+//!   bytes to = 5;        // absent for contract creation
+//!   bytes value = 6;
+//!   bytes data = 7;
+//!   uint64 v = 8;        // as in the RLP
+//!   bytes r = 9;
+//!   bytes s = 10;
+//! }
+//! ```
+
+use bytes::Bytes;
+use ethereum_types::{Address, U256};
+use rlp::DecoderError;
+
+use transaction::{Action, SignatureComponents, Transaction};
+
+/// Version of the protobuf transaction encoding.
+const FORMAT_VERSION: u64 = 1;
+
+/// Encodes the transaction and its signature as a `Transaction` message.
+pub fn encode(unsigned: &Transaction, signature: &SignatureComponents) -> Bytes {
+    let mut out = Bytes::new();
+    write_key(&mut out, 1, 0);
+    write_varint(&mut out, FORMAT_VERSION);
+    write_u256(&mut out, 2, &unsigned.nonce);
+    write_u256(&mut out, 3, &unsigned.gas_price);
+    write_u256(&mut out, 4, &unsigned.gas);
+    if let Action::Call(ref to) = unsigned.action {
+        write_bytes(&mut out, 5, to);
+    }
+    write_u256(&mut out, 6, &unsigned.value);
+    write_bytes(&mut out, 7, &unsigned.data);
+    write_key(&mut out, 8, 0);
+    write_varint(&mut out, signature.v);
+    write_u256(&mut out, 9, &signature.r);
+    write_u256(&mut out, 10, &signature.s);
+    out
+}
+
+/// Decodes a `Transaction` message. Missing fields default to zero (and a missing `to` to
+/// contract creation), unknown fields are rejected.
+pub fn decode(mut bytes: &[u8]) -> Result<(Transaction, SignatureComponents), DecoderError> {
+    let mut version = None;
+    let mut unsigned = Transaction::default();
+    let mut signature = SignatureComponents {
+        v: 0,
+        r: U256::zero(),
+        s: U256::zero(),
+    };
+    while !bytes.is_empty() {
+        let key = read_varint(&mut bytes)?;
+        match (key >> 3, key & 7) {
+            (1, 0) => version = Some(read_varint(&mut bytes)?),
+            (2, 2) => unsigned.nonce = read_u256(&mut bytes)?,
+            (3, 2) => unsigned.gas_price = read_u256(&mut bytes)?,
+            (4, 2) => unsigned.gas = read_u256(&mut bytes)?,
+            (5, 2) => {
+                let to = read_bytes(&mut bytes)?;
+                if to.len() != 20 {
+                    return Err(DecoderError::Custom("Invalid proto field: to"));
+                }
+                unsigned.action = Action::Call(Address::from_slice(to));
+            }
+            (6, 2) => unsigned.value = read_u256(&mut bytes)?,
+            (7, 2) => unsigned.data = read_bytes(&mut bytes)?.to_vec(),
+            (8, 0) => signature.v = read_varint(&mut bytes)?,
+            (9, 2) => signature.r = read_u256(&mut bytes)?,
+            (10, 2) => signature.s = read_u256(&mut bytes)?,
+            _ => return Err(DecoderError::Custom("Unknown proto field")),
+        }
+    }
+    match version {
+        Some(FORMAT_VERSION) => Ok((unsigned, signature)),
+        Some(_) => Err(DecoderError::Custom(
+            "Unknown proto transaction format version",
+        )),
+        None => Err(DecoderError::Custom("Missing proto field: version")),
+    }
+}
+
+fn write_varint(out: &mut Bytes, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn write_key(out: &mut Bytes, field: u64, wire_type: u64) {
+    write_varint(out, (field << 3) | wire_type);
+}
+
+fn write_bytes(out: &mut Bytes, field: u64, bytes: &[u8]) {
+    write_key(out, field, 2);
+    write_varint(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+/// Writes `value` as a length-delimited field holding its minimal big-endian bytes.
+fn write_u256(out: &mut Bytes, field: u64, value: &U256) {
+    let mut buf = [0u8; 32];
+    value.to_big_endian(&mut buf);
+    let start = buf.iter().position(|b| *b != 0).unwrap_or(buf.len());
+    write_bytes(out, field, &buf[start..]);
+}
+
+fn read_varint(bytes: &mut &[u8]) -> Result<u64, DecoderError> {
+    let mut value = 0u64;
+    let mut shift = 0;
+    loop {
+        let (byte, rest) = bytes
+            .split_first()
+            .ok_or(DecoderError::Custom("Truncated proto message"))?;
+        *bytes = rest;
+        // the 10th byte carries only the top bit of a `u64`
+        if shift == 63 && *byte > 1 {
+            return Err(DecoderError::Custom("Invalid proto varint"));
+        }
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+        shift += 7;
+    }
+}
+
+fn read_bytes<'a>(bytes: &mut &'a [u8]) -> Result<&'a [u8], DecoderError> {
+    let len = read_varint(bytes)? as usize;
+    if bytes.len() < len {
+        return Err(DecoderError::Custom("Truncated proto message"));
+    }
+    let (value, rest) = bytes.split_at(len);
+    *bytes = rest;
+    Ok(value)
+}
+
+fn read_u256(bytes: &mut &[u8]) -> Result<U256, DecoderError> {
+    let value = read_bytes(bytes)?;
+    if value.len() > 32 {
+        return Err(DecoderError::Custom(
+            "Proto number does not fit in 256 bits",
+        ));
+    }
+    Ok(U256::from(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_reject_overflowing_varints() {
+        let read = |bytes: &[u8]| {
+            let mut bytes = bytes;
+            read_varint(&mut bytes)
+        };
+        for value in &[0, 1, 127, 128, 300, u64::max_value() - 1, u64::max_value()] {
+            let mut out = Bytes::new();
+            write_varint(&mut out, *value);
+            assert_eq!(read(&out), Ok(*value));
+        }
+
+        let invalid = Err(DecoderError::Custom("Invalid proto varint"));
+        let mut max = vec![0xff; 9];
+        max.push(0x01);
+        assert_eq!(read(&max), Ok(u64::max_value()));
+        let mut overflowing = vec![0xff; 9];
+        overflowing.push(0x02);
+        assert_eq!(read(&overflowing), invalid);
+        let mut eleven_bytes = vec![0xff; 10];
+        eleven_bytes.push(0x01);
+        assert_eq!(read(&eleven_bytes), invalid);
+        let mut overlong_zero = vec![0x80; 10];
+        overlong_zero.push(0x00);
+        assert_eq!(read(&overlong_zero), invalid);
+        assert_eq!(
+            read(&[0x80]),
+            Err(DecoderError::Custom("Truncated proto message"))
+        );
+
+        let mut tx = vec![0x08];
+        tx.extend_from_slice(&overflowing);
+        assert_eq!(decode(&tx).err(), invalid.err());
+    }
+
+    #[test]
+    fn should_report_proto_format_errors() {
+        let truncated = Some(DecoderError::Custom("Truncated proto message"));
+        // `data` field claiming 5 bytes with only 1 present
+        assert_eq!(decode(&[0x08, 0x01, 0x3a, 0x05, 0x00]).err(), truncated);
+
+        let mut oversized = vec![0x08, 0x01, 0x12, 33];
+        oversized.extend_from_slice(&[0xff; 33]);
+        assert_eq!(
+            decode(&oversized).err(),
+            Some(DecoderError::Custom(
+                "Proto number does not fit in 256 bits"
+            ))
+        );
+    }
+}
@@ -16,16 +16,20 @@
 
 //! Transaction data structure.
 
-use std::ops::Deref;
+use std::{
+    cmp::{self, Reverse},
+    ops::Deref,
+};
 
 use ethereum_types::{Address, H160, H256, U256};
 use ethjson;
-use ethkey::{self, public_to_address, recover, Public, Secret, Signature};
+use ethkey::{self, public_to_address, recover, KeyPair, Public, Secret, Signature};
 use hash::keccak;
 use heapsize::HeapSizeOf;
 use rlp::{self, DecoderError, Encodable, Rlp, RlpStream};
+use rustc_hex::{FromHex, ToHex};
 
-use transaction::error;
+use transaction::{error, proto};
 
 type Bytes = Vec<u8>;
 type BlockNumber = u64;
@@ -55,6 +59,8 @@ impl Default for Action {
     }
 }
 
+/// `Create` is encoded as the empty string, while `Call` always carries the full 20-byte
+/// address, so a call to the zero address (20 zero bytes) is distinct from a create.
 impl rlp::Decodable for Action {
     fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
         if rlp.is_empty() {
@@ -71,6 +77,7 @@ impl rlp::Decodable for Action {
 
 impl rlp::Encodable for Action {
     fn rlp_append(&self, s: &mut RlpStream) {
+        // must stay distinguishable on decode: `Call(0x0)` is 20 zero bytes, not the empty string
         match *self {
             Action::Create => s.append_internal(&""),
             Action::Call(ref addr) => s.append_internal(addr),
@@ -87,6 +94,26 @@ pub enum Condition {
     Timestamp(u64),
 }
 
+impl Condition {
+    /// Estimated number of blocks after `best_block` until the condition is met, 0 if it already is.
+    /// Timestamp conditions are converted with `avg_block_time` (in seconds), rounding up.
+    pub fn blocks_until_ready(
+        &self,
+        best_block: BlockNumber,
+        now: u64,
+        avg_block_time: u64,
+    ) -> u64 {
+        match *self {
+            Condition::Number(number) => number.saturating_sub(best_block),
+            Condition::Timestamp(time) => {
+                let delta = time.saturating_sub(now);
+                let avg_block_time = cmp::max(avg_block_time, 1);
+                delta / avg_block_time + if delta % avg_block_time == 0 { 0 } else { 1 }
+            }
+        }
+    }
+}
+
 /// Replay protection logic for v part of transaction's signature
 pub mod signature {
     /// Adds chain id into v
@@ -110,6 +137,76 @@ pub mod signature {
     }
 }
 
+/// Gas costs used to compute the intrinsic gas of a transaction.
+///
+/// Consensus code derives these from `vm::Schedule` (see `ethcore::transaction_ext`), the
+/// presets below only mirror its values for code that has no `vm::Schedule` at hand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GasSchedule {
+    /// Gas paid by every transaction.
+    pub base_gas: u64,
+    /// Additional gas paid by contract creation transactions.
+    pub create_gas: u64,
+    /// Gas paid for every zero byte of transaction data.
+    pub zero_byte_gas: u64,
+    /// Gas paid for every non-zero byte of transaction data.
+    pub non_zero_byte_gas: u64,
+}
+
+impl GasSchedule {
+    /// Frontier costs.
+    pub fn frontier() -> Self {
+        GasSchedule {
+            base_gas: 21000,
+            create_gas: 0,
+            zero_byte_gas: 4,
+            non_zero_byte_gas: 68,
+        }
+    }
+
+    /// Homestead costs, charging extra for contract creation (EIP-2).
+    pub fn homestead() -> Self {
+        GasSchedule {
+            create_gas: 32000,
+            ..GasSchedule::frontier()
+        }
+    }
+
+    /// Istanbul costs, with cheaper non-zero data bytes (EIP-2028).
+    pub fn istanbul() -> Self {
+        GasSchedule {
+            non_zero_byte_gas: 16,
+            ..GasSchedule::homestead()
+        }
+    }
+}
+
+/// Intrinsic gas of a transaction, itemized by what it is charged for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct GasBreakdown {
+    /// Gas paid by every transaction.
+    pub base: u64,
+    /// Gas paid for contract creation.
+    pub create: u64,
+    /// Gas paid for zero bytes of transaction data.
+    pub calldata_zero: u64,
+    /// Gas paid for non-zero bytes of transaction data.
+    pub calldata_nonzero: u64,
+}
+
+impl GasBreakdown {
+    /// Sum of all items, i.e. the intrinsic gas.
+    pub fn total(&self) -> u64 {
+        self.base + self.create + self.calldata_zero + self.calldata_nonzero
+    }
+}
+
+/// Version of the compact transaction encoding used for persistence.
+const COMPACT_FORMAT_VERSION: u8 = 1;
+
+/// Selector of ERC-20 `transfer(address,uint256)`.
+const ERC20_TRANSFER_SELECTOR: [u8; 4] = [0xa9, 0x05, 0x9c, 0xbb];
+
 /// A set of information describing an externally-originating message call
 /// or contract creation operation.
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
@@ -122,14 +219,58 @@ pub struct Transaction {
     pub gas: U256,
     /// Action, can be either call or contract create.
     pub action: Action,
-    /// Transfered value.
+    /// Transferred value.
     pub value: U256,
     /// Transaction data.
     pub data: Bytes,
 }
 
 impl Transaction {
+    /// Create a transaction from its individual fields.
+    pub fn from_fields(
+        nonce: U256,
+        gas_price: U256,
+        gas: U256,
+        action: Action,
+        value: U256,
+        data: Bytes,
+    ) -> Transaction {
+        Transaction {
+            nonce,
+            gas_price,
+            gas,
+            action,
+            value,
+            data,
+        }
+    }
+
+    /// Fake-signed transaction from `from` for dry runs like gas estimation, with zero gas price.
+    /// `gas` is the upper bound to estimate with, typically the block gas limit; it must leave
+    /// room for the gas already used in the block, as the executive adds the two.
+    pub fn for_estimation(
+        from: Address,
+        action: Action,
+        value: U256,
+        data: Bytes,
+        gas: U256,
+    ) -> SignedTransaction {
+        Transaction {
+            nonce: U256::zero(),
+            gas_price: U256::zero(),
+            gas,
+            action,
+            value,
+            data,
+        }
+        .fake_sign(from)
+    }
+
     /// Append object with a without signature into RLP stream
+    ///
+    /// Without `chain_id` this is the 6-field pre-EIP-155 signing pre-image, with `chain_id` the
+    /// 9-field EIP-155 one (`chain_id, 0, 0` appended). Either way the signed transaction is
+    /// stored as 9 fields, with `v` telling the pre-images apart (27/28 vs `>= 35`).
     pub fn rlp_append_unsigned_transaction(&self, s: &mut RlpStream, chain_id: Option<u64>) {
         s.begin_list(if chain_id.is_none() { 6 } else { 9 });
         s.append(&self.nonce);
@@ -190,9 +331,11 @@ impl From<ethjson::transaction::Transaction> for UnverifiedTransaction {
                 value: t.value.into(),
                 data: t.data.into(),
             },
-            r: t.r.into(),
-            s: t.s.into(),
-            v: t.v.into(),
+            signature: SignatureComponents {
+                v: t.v.into(),
+                r: t.r.into(),
+                s: t.s.into(),
+            },
             hash: 0.into(),
         }
         .compute_hash()
@@ -202,11 +345,395 @@ impl From<ethjson::transaction::Transaction> for UnverifiedTransaction {
 impl Transaction {
     /// The message hash of the transaction.
     pub fn hash(&self, chain_id: Option<u64>) -> H256 {
+        keccak(self.rlp_unsigned(chain_id))
+    }
+
+    /// RLP of the transaction without signature, as hashed for signing.
+    pub fn rlp_unsigned(&self, chain_id: Option<u64>) -> Bytes {
         let mut stream = RlpStream::new();
         self.rlp_append_unsigned_transaction(&mut stream, chain_id);
+        stream.out()
+    }
+
+    /// Hash of the unsigned transaction content, excluding signature and chain ID, so that
+    /// the same transaction template yields the same hash on every chain.
+    /// Equal to the signing hash of a transaction without replay protection.
+    pub fn content_hash(&self) -> H256 {
+        self.hash(None)
+    }
+
+    /// Address called by the transaction, or `None` for contract creation.
+    pub fn call_target(&self) -> Option<Address> {
+        match self.action {
+            Action::Call(to) => Some(to),
+            Action::Create => None,
+        }
+    }
+
+    /// Signature-independent key identifying a transaction from given sender, so that
+    /// differently signed submissions of the same transaction map to the same key.
+    pub fn idempotency_key(&self, sender: Address) -> H256 {
+        let mut stream = RlpStream::new_list(2);
+        stream.append(&sender);
+        self.rlp_append_unsigned_transaction(&mut stream, None);
         keccak(stream.as_raw())
     }
 
+    /// Addresses to insert into an address bloom for this transaction: the sender and,
+    /// for calls, the recipient.
+    pub fn address_bloom_inputs(&self, sender: Address) -> Vec<Address> {
+        match self.action {
+            Action::Call(to) => vec![sender, to],
+            Action::Create => vec![sender],
+        }
+    }
+
+    /// Checks if the transaction carries an EIP-2930 access list. Never the case for the legacy
+    /// transactions of this version.
+    pub fn has_access_list(&self) -> bool {
+        false
+    }
+
+    /// Number of entries in the access list, 0 without one.
+    pub fn access_list_len(&self) -> usize {
+        0
+    }
+
+    /// Checks if this is a legacy (untyped, pre-EIP-2718) transaction, the only kind supported
+    /// by this version.
+    pub fn is_legacy(&self) -> bool {
+        true
+    }
+
+    /// Length of the transaction data in bytes.
+    pub fn data_len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Checks if the transaction data is longer than `threshold` bytes.
+    pub fn is_large_calldata(&self, threshold: usize) -> bool {
+        self.data.len() > threshold
+    }
+
+    /// Shannon entropy of the transaction data in bits per byte, from `0.0` (empty or a single
+    /// repeated byte) to `8.0`. Large payloads with low entropy are likely padding.
+    pub fn data_entropy(&self) -> f64 {
+        if self.data.is_empty() {
+            return 0.0;
+        }
+        let mut counts = [0usize; 256];
+        for byte in &self.data {
+            counts[*byte as usize] += 1;
+        }
+        let len = self.data.len() as f64;
+        counts
+            .iter()
+            .filter(|count| **count > 0)
+            .map(|count| {
+                let p = *count as f64 / len;
+                -p * p.log2()
+            })
+            .sum()
+    }
+
+    /// Gas charged for the transaction data, given the cost of zero and non-zero bytes.
+    pub fn calldata_gas(&self, zero_byte_cost: u64, nonzero_byte_cost: u64) -> u64 {
+        self.data.iter().fold(0, |gas, byte| {
+            gas + match *byte {
+                0 => zero_byte_cost,
+                _ => nonzero_byte_cost,
+            }
+        })
+    }
+
+    /// Gas required before any execution takes place, under given gas schedule.
+    pub fn intrinsic_gas(&self, schedule: &GasSchedule) -> u64 {
+        self.gas_breakdown(schedule).total()
+    }
+
+    /// Intrinsic gas under given gas schedule, itemized. With the schedule derived from a
+    /// `vm::Schedule` the items add up to the gas charged by consensus.
+    pub fn gas_breakdown(&self, schedule: &GasSchedule) -> GasBreakdown {
+        let zero_bytes = self.data.iter().filter(|b| **b == 0).count() as u64;
+        let nonzero_bytes = self.data.len() as u64 - zero_bytes;
+        GasBreakdown {
+            base: schedule.base_gas,
+            create: match self.action {
+                Action::Create => schedule.create_gas,
+                Action::Call(_) => 0,
+            },
+            calldata_zero: zero_bytes * schedule.zero_byte_gas,
+            calldata_nonzero: nonzero_bytes * schedule.non_zero_byte_gas,
+        }
+    }
+
+    /// Checks if the transaction calls a precompile, i.e. an address in `1..=max_precompile_addr`.
+    pub fn targets_precompile(&self, max_precompile_addr: u8) -> bool {
+        match self.action {
+            Action::Call(to) => {
+                let (last, rest) = to.split_last().expect("address is 20 bytes; qed");
+                rest.iter().all(|b| *b == 0) && *last != 0 && *last <= max_precompile_addr
+            }
+            Action::Create => false,
+        }
+    }
+
+    /// Checks if this is a plain value transfer: a call with no data and a non-zero value.
+    pub fn is_simple_transfer(&self) -> bool {
+        match self.action {
+            Action::Call(_) => self.data.is_empty() && !self.value.is_zero(),
+            Action::Create => false,
+        }
+    }
+
+    /// Checks if this is a contract creation that also transfers value to the new contract.
+    pub fn is_funded_create(&self) -> bool {
+        match self.action {
+            Action::Create => !self.value.is_zero(),
+            Action::Call(_) => false,
+        }
+    }
+
+    /// Checks if this is a call whose data starts with given 4-byte method selector.
+    pub fn matches_selector(&self, selector: [u8; 4]) -> bool {
+        match self.action {
+            Action::Call(_) => self.data.len() >= 4 && self.data[..4] == selector,
+            Action::Create => false,
+        }
+    }
+
+    /// Recipient and amount of an ERC-20 `transfer(address,uint256)` call, if the data looks
+    /// like one. Only the selector and data length are checked, no full ABI decoding is done.
+    pub fn decoded_erc20_transfer(&self) -> Option<(Address, U256)> {
+        match self.action {
+            Action::Call(_)
+                if self.data.len() >= 68 && self.data[..4] == ERC20_TRANSFER_SELECTOR =>
+            {
+                Some((
+                    Address::from_slice(&self.data[16..36]),
+                    U256::from(&self.data[36..68]),
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    /// Salt and init code of a call to a CREATE2 factory, assuming the factory method takes
+    /// `(bytes32 salt, bytes initCode)`. `is_factory_selector` decides which 4-byte method
+    /// selectors are treated as factory deployments.
+    pub fn create2_args<F>(&self, is_factory_selector: F) -> Option<(H256, Bytes)>
+    where
+        F: Fn(&[u8]) -> bool,
+    {
+        if self.action == Action::Create
+            || self.data.len() < 68
+            || !is_factory_selector(&self.data[..4])
+        {
+            return None;
+        }
+
+        let args = &self.data[4..];
+        let salt = H256::from_slice(&args[..32]);
+        let offset = U256::from(&args[32..64]);
+        if offset > U256::from(args.len() as u64) {
+            return None;
+        }
+        let code_start = offset.low_u64() as usize + 32;
+        if code_start > args.len() {
+            return None;
+        }
+        let code_len = U256::from(&args[code_start - 32..code_start]);
+        if code_len > U256::from((args.len() - code_start) as u64) {
+            return None;
+        }
+        let code_end = code_start + code_len.low_u64() as usize;
+        Some((salt, args[code_start..code_end].to_vec()))
+    }
+
+    /// Checks if this is a call carrying neither value nor data, typically sent only to
+    /// replace (cancel) another transaction with the same nonce.
+    pub fn is_nonce_bump(&self) -> bool {
+        match self.action {
+            Action::Call(_) => self.value.is_zero() && self.data.is_empty(),
+            Action::Create => false,
+        }
+    }
+
+    /// Clamps the gas price into the `[min, max]` range.
+    /// The transaction has to be signed again afterwards.
+    ///
+    /// `min` must not exceed `max`; this is checked in debug builds only, otherwise `min` takes
+    /// precedence.
+    pub fn clamp_gas_price(&mut self, min: U256, max: U256) {
+        debug_assert!(min <= max, "clamp_gas_price called with min > max");
+        if self.gas_price < min {
+            self.gas_price = min;
+        } else if self.gas_price > max {
+            self.gas_price = max;
+        }
+    }
+
+    /// Gas price effectively paid under given base fee, `None` before London.
+    ///
+    /// With a base fee this is the EIP-1559 `min(max_fee, base_fee + max_priority_fee)`, where
+    /// both caps are the gas price for the legacy transactions of this version, so it always
+    /// comes down to the gas price.
+    pub fn effective_gas_price_opt(&self, base_fee: Option<U256>) -> U256 {
+        match base_fee {
+            Some(base_fee) => cmp::min(self.gas_price, base_fee.saturating_add(self.gas_price)),
+            None => self.gas_price,
+        }
+    }
+
+    /// Whether the gas price covers given base fee, i.e. the transaction is still includable.
+    pub fn can_pay_base_fee(&self, base_fee: U256) -> bool {
+        self.gas_price >= base_fee
+    }
+
+    /// Amount paid above `target_price` if all gas is used: `(gas_price - target_price) * gas`,
+    /// or zero if the gas price does not exceed the target.
+    pub fn overpayment(&self, target_price: U256) -> U256 {
+        self.gas_price
+            .saturating_sub(target_price)
+            .saturating_mul(self.gas)
+    }
+
+    /// Index of the highest of the ascending `buckets` thresholds the effective gas price under
+    /// `base_fee` reaches, for gas price histograms. `None` if the price is below all thresholds.
+    pub fn gas_price_bucket(&self, base_fee: Option<U256>, buckets: &[U256]) -> Option<usize> {
+        let gas_price = self.effective_gas_price_opt(base_fee);
+        buckets
+            .iter()
+            .rposition(|threshold| gas_price >= *threshold)
+    }
+
+    /// Checks if the gas price is below given base fee, i.e. the transaction can not be included
+    /// until the base fee drops.
+    pub fn is_underpriced(&self, base_fee: U256) -> bool {
+        !self.can_pay_base_fee(base_fee)
+    }
+
+    /// Total amount debited from the sender for `gas_used`:
+    /// `value + effective_gas_price * gas_used`, with the effective gas price under `base_fee`.
+    pub fn total_debit(&self, base_fee: Option<U256>, gas_used: U256) -> U256 {
+        self.effective_gas_price_opt(base_fee)
+            .saturating_mul(gas_used)
+            .saturating_add(self.value)
+    }
+
+    /// Total cost of the transaction after execution, with `refund` capped at a fifth of
+    /// `gas_used` (EIP-3529): `value + (gas_used - min(refund, gas_used / 5)) * gas_price`.
+    pub fn cost_after_max_refund(&self, gas_used: U256, refund: U256) -> U256 {
+        let refund = cmp::min(refund, gas_used / U256::from(5));
+        (gas_used - refund)
+            .saturating_mul(self.gas_price)
+            .saturating_add(self.value)
+    }
+
+    /// Percentage by which the gas price exceeds `base_fee`, saturating at `u64::max_value()`.
+    /// A gas price below the base fee yields `Some(0)`, a zero base fee yields `None`.
+    pub fn tip_percent_over_base(&self, base_fee: U256) -> Option<u64> {
+        if base_fee.is_zero() {
+            return None;
+        }
+        let percent = self
+            .gas_price
+            .saturating_sub(base_fee)
+            .saturating_mul(U256::from(100))
+            / base_fee;
+        Some(if percent > U256::from(u64::max_value()) {
+            u64::max_value()
+        } else {
+            percent.low_u64()
+        })
+    }
+
+    /// Gas price a transaction replacing this one must exceed, given the required bump in
+    /// percent: `gas_price * (100 + bump_percent) / 100`, saturating on overflow.
+    pub fn min_replacement_price(&self, bump_percent: u64) -> U256 {
+        let factor = U256::from(100).saturating_add(U256::from(bump_percent));
+        match self.gas_price.overflowing_mul(factor) {
+            (_, true) => U256::max_value(),
+            (price, false) => price / U256::from(100),
+        }
+    }
+
+    /// Tip received by the block author for `gas_used`, i.e. the part of the gas price above
+    /// the base fee. Zero if the gas price does not cover the base fee.
+    pub fn miner_reward(&self, base_fee: U256, gas_used: U256) -> U256 {
+        self.gas_price
+            .saturating_sub(base_fee)
+            .saturating_mul(gas_used)
+    }
+
+    /// Amount burned under EIP-1559 for `gas_used`: `base_fee * gas_used`, the same for every
+    /// transaction type.
+    pub fn burns_base_fee(&self, base_fee: U256, gas_used: U256) -> U256 {
+        base_fee.saturating_mul(gas_used)
+    }
+
+    /// Checks that the transaction gas does not exceed given block gas limit.
+    pub fn check_within_block_gas_limit(&self, block_gas_limit: U256) -> Result<(), error::Error> {
+        if self.gas > block_gas_limit {
+            return Err(error::Error::GasLimitExceeded {
+                limit: block_gas_limit,
+                got: self.gas,
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks that the transaction gas does not exceed given per-transaction gas cap.
+    pub fn check_tx_gas_cap(&self, cap: U256) -> Result<(), error::Error> {
+        if self.gas > cap {
+            return Err(error::Error::TxGasCapExceeded { got: self.gas, cap });
+        }
+        Ok(())
+    }
+
+    /// Rejects transactions with a zero gas limit, which can never execute.
+    pub fn check_nonzero_gas(&self) -> Result<(), error::Error> {
+        if self.gas.is_zero() {
+            return Err(error::Error::ZeroGasLimit);
+        }
+        Ok(())
+    }
+
+    /// Returns the nonce as `u64`, or an error if it does not fit.
+    pub fn check_nonce_u64(&self) -> Result<u64, error::Error> {
+        if self.nonce > U256::from(u64::max_value()) {
+            return Err(error::Error::NonceOverflow(self.nonce));
+        }
+        Ok(self.nonce.low_u64())
+    }
+
+    /// Checks that the nonce is at most `max_future` ahead of the sender's `account_nonce`.
+    pub fn check_nonce_sane(
+        &self,
+        account_nonce: U256,
+        max_future: u64,
+    ) -> Result<(), error::Error> {
+        let limit = account_nonce.saturating_add(U256::from(max_future));
+        if self.nonce > limit {
+            return Err(error::Error::NonceTooFarInFuture {
+                limit,
+                got: self.nonce,
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks the transaction against optional sanity limits, like a gas price ceiling.
+    pub fn verify_limits(&self, max_gas_price: Option<U256>) -> Result<(), error::Error> {
+        match max_gas_price {
+            Some(limit) if self.gas_price > limit => Err(error::Error::GasPriceTooHigh {
+                limit,
+                got: self.gas_price,
+            }),
+            _ => Ok(()),
+        }
+    }
+
     /// Signs the transaction as coming from `sender`.
     pub fn sign(self, secret: &Secret, chain_id: Option<u64>) -> SignedTransaction {
         let sig = ::ethkey::sign(secret, &self.hash(chain_id))
@@ -219,22 +746,44 @@ impl Transaction {
     pub fn with_signature(self, sig: Signature, chain_id: Option<u64>) -> UnverifiedTransaction {
         UnverifiedTransaction {
             unsigned: self,
-            r: sig.r().into(),
-            s: sig.s().into(),
-            v: signature::add_chain_replay_protection(sig.v() as u64, chain_id),
+            signature: SignatureComponents {
+                v: signature::add_chain_replay_protection(sig.v() as u64, chain_id),
+                r: sig.r().into(),
+                s: sig.s().into(),
+            },
             hash: 0.into(),
         }
         .compute_hash()
     }
 
+    /// Signs the transaction with a signature produced externally for `expected_hash`.
+    /// Fails if `expected_hash` is not the signing hash of this transaction.
+    pub fn with_signature_for_hash(
+        self,
+        sig: Signature,
+        chain_id: Option<u64>,
+        expected_hash: H256,
+    ) -> Result<UnverifiedTransaction, error::Error> {
+        let computed = self.hash(chain_id);
+        if computed != expected_hash {
+            return Err(error::Error::SigningHashMismatch {
+                expected: expected_hash,
+                computed,
+            });
+        }
+        Ok(self.with_signature(sig, chain_id))
+    }
+
     /// Useful for test incorrectly signed transactions.
     #[cfg(test)]
     pub fn invalid_sign(self) -> UnverifiedTransaction {
         UnverifiedTransaction {
             unsigned: self,
-            r: U256::one(),
-            s: U256::one(),
-            v: 0,
+            signature: SignatureComponents {
+                v: 0,
+                r: U256::one(),
+                s: U256::one(),
+            },
             hash: 0.into(),
         }
         .compute_hash()
@@ -245,9 +794,11 @@ impl Transaction {
         SignedTransaction {
             transaction: UnverifiedTransaction {
                 unsigned: self,
-                r: U256::one(),
-                s: U256::one(),
-                v: 0,
+                signature: SignatureComponents {
+                    v: 0,
+                    r: U256::one(),
+                    s: U256::one(),
+                },
                 hash: 0.into(),
             }
             .compute_hash(),
@@ -264,9 +815,11 @@ impl Transaction {
         SignedTransaction {
             transaction: UnverifiedTransaction {
                 unsigned: self,
-                r: U256::zero(),
-                s: U256::zero(),
-                v: chain_id,
+                signature: SignatureComponents {
+                    v: chain_id,
+                    r: U256::zero(),
+                    s: U256::zero(),
+                },
                 hash: 0.into(),
             }
             .compute_hash(),
@@ -276,18 +829,49 @@ impl Transaction {
     }
 }
 
-/// Signed transaction information without verified signature.
+/// Components that constitute transaction signature.
 #[derive(Debug, Clone, Eq, PartialEq)]
-pub struct UnverifiedTransaction {
-    /// Plain Transaction.
-    unsigned: Transaction,
+pub struct SignatureComponents {
     /// The V field of the signature; the LS bit described which half of the curve our point falls
     /// in. The MS bits describe which chain this transaction is for. If 27/28, its for all chains.
-    v: u64,
+    pub v: u64,
     /// The R field of the signature; helps describe the point on the curve.
-    r: U256,
+    pub r: U256,
     /// The S field of the signature; helps describe the point on the curve.
-    s: U256,
+    pub s: U256,
+}
+
+impl SignatureComponents {
+    /// Encodes the signature alone, as RLP list `[v, r, s]`.
+    pub fn encode_standalone(&self) -> Vec<u8> {
+        let mut s = RlpStream::new_list(3);
+        s.append(&self.v);
+        s.append(&self.r);
+        s.append(&self.s);
+        s.out()
+    }
+
+    /// Decodes a signature encoded with `encode_standalone`.
+    pub fn decode_standalone(bytes: &[u8]) -> Result<SignatureComponents, DecoderError> {
+        let d = Rlp::new(bytes);
+        if d.item_count()? != 3 {
+            return Err(DecoderError::RlpIncorrectListLen);
+        }
+        Ok(SignatureComponents {
+            v: d.val_at(0)?,
+            r: d.val_at(1)?,
+            s: d.val_at(2)?,
+        })
+    }
+}
+
+/// Signed transaction information without verified signature.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct UnverifiedTransaction {
+    /// Plain Transaction.
+    unsigned: Transaction,
+    /// Transaction signature.
+    signature: SignatureComponents,
     /// Hash of the transaction
     hash: H256,
 }
@@ -306,6 +890,30 @@ impl Deref for UnverifiedTransaction {
     }
 }
 
+/// Decodes the transaction field at `index`, replacing the error with one naming the field
+/// and the kind of the original error.
+macro_rules! decode_field {
+    ($d:expr, $index:expr, $field:expr) => {
+        $d.val_at($index).map_err(|e| {
+            DecoderError::Custom(match e {
+                DecoderError::RlpIsTooBig => {
+                    concat!("Invalid transaction field: ", $field, " (too big)")
+                }
+                DecoderError::RlpIsTooShort | DecoderError::RlpInconsistentLengthAndData => {
+                    concat!("Invalid transaction field: ", $field, " (truncated)")
+                }
+                DecoderError::RlpExpectedToBeData => {
+                    concat!("Invalid transaction field: ", $field, " (expected data)")
+                }
+                DecoderError::RlpInvalidIndirection | DecoderError::RlpDataLenWithZeroPrefix => {
+                    concat!("Invalid transaction field: ", $field, " (not canonical)")
+                }
+                _ => concat!("Invalid transaction field: ", $field),
+            })
+        })
+    };
+}
+
 impl rlp::Decodable for UnverifiedTransaction {
     fn decode(d: &Rlp) -> Result<Self, DecoderError> {
         if d.item_count()? != 9 {
@@ -314,16 +922,18 @@ impl rlp::Decodable for UnverifiedTransaction {
         let hash = keccak(d.as_raw());
         Ok(UnverifiedTransaction {
             unsigned: Transaction {
-                nonce: d.val_at(0)?,
-                gas_price: d.val_at(1)?,
-                gas: d.val_at(2)?,
-                action: d.val_at(3)?,
-                value: d.val_at(4)?,
-                data: d.val_at(5)?,
+                nonce: decode_field!(d, 0, "nonce")?,
+                gas_price: decode_field!(d, 1, "gas_price")?,
+                gas: decode_field!(d, 2, "gas")?,
+                action: decode_field!(d, 3, "action")?,
+                value: decode_field!(d, 4, "value")?,
+                data: decode_field!(d, 5, "data")?,
+            },
+            signature: SignatureComponents {
+                v: decode_field!(d, 6, "v")?,
+                r: decode_field!(d, 7, "r")?,
+                s: decode_field!(d, 8, "s")?,
             },
-            v: d.val_at(6)?,
-            r: d.val_at(7)?,
-            s: d.val_at(8)?,
             hash,
         })
     }
@@ -345,7 +955,74 @@ impl UnverifiedTransaction {
 
     /// Checks if the signature is empty.
     pub fn is_unsigned(&self) -> bool {
-        self.r.is_zero() && self.s.is_zero()
+        self.signature.r.is_zero() && self.signature.s.is_zero()
+    }
+
+    /// Decodes a transaction, additionally rejecting signed transactions whose `v` is neither
+    /// `27`, `28` nor `>= 35`.
+    pub fn decode_strict(raw: &[u8]) -> Result<Self, DecoderError> {
+        let tx: UnverifiedTransaction = rlp::decode(raw)?;
+        if !tx.is_unsigned() && tx.standard_v() > 1 {
+            return Err(DecoderError::Custom("Invalid transaction field: v"));
+        }
+        Ok(tx)
+    }
+
+    /// Decodes a transaction, rejecting any bytes following its RLP.
+    pub fn decode_exact(raw: &[u8]) -> Result<Self, DecoderError> {
+        let rlp = Rlp::new(raw);
+        if raw.len() != rlp.payload_info()?.total() {
+            return Err(DecoderError::RlpIsTooBig);
+        }
+        rlp.as_val()
+    }
+
+    /// Decodes `SignedTransaction::to_compact_bytes` output into the transaction and the stored
+    /// sender. The sender is read as is and is only a hint: storage can be corrupted or
+    /// tampered with, so it must not be trusted without `SignedTransaction::new`.
+    pub fn from_compact_bytes(bytes: &[u8]) -> Result<(Self, Address), DecoderError> {
+        match bytes.split_first() {
+            Some((&COMPACT_FORMAT_VERSION, rest)) if rest.len() >= 20 => {
+                Ok((rlp::decode(&rest[20..])?, Address::from_slice(&rest[..20])))
+            }
+            Some((&COMPACT_FORMAT_VERSION, _)) | None => Err(DecoderError::RlpIsTooShort),
+            Some(_) => Err(DecoderError::Custom(
+                "Unknown compact transaction format version",
+            )),
+        }
+    }
+
+    /// Decodes a transaction from its hex-encoded RLP, with or without the `0x` prefix.
+    pub fn from_hex(s: &str) -> Result<Self, error::Error> {
+        let s = s.trim();
+        let s = s.trim_start_matches("0x");
+        let bytes: Bytes = s
+            .from_hex()
+            .map_err(|e| error::Error::InvalidRlp(format!("Invalid hex: {}", e)))?;
+        Ok(rlp::decode(&bytes)?)
+    }
+
+    /// Returns the `0x`-prefixed hex encoding of the transaction RLP.
+    pub fn to_hex(&self) -> String {
+        format!("0x{}", self.rlp_bytes().to_hex())
+    }
+
+    /// Encodes the transaction as a protobuf message, for services without an RLP codec.
+    /// See the `proto` module for the schema.
+    pub fn to_proto_bytes(&self) -> Bytes {
+        proto::encode(&self.unsigned, &self.signature)
+    }
+
+    /// Decodes a transaction from `to_proto_bytes` output. Missing fields default to zero
+    /// (and a missing `to` to contract creation), unknown fields are rejected.
+    pub fn from_proto_bytes(bytes: &[u8]) -> Result<Self, DecoderError> {
+        let (unsigned, signature) = proto::decode(bytes)?;
+        Ok(UnverifiedTransaction {
+            unsigned,
+            signature,
+            hash: H256::zero(),
+        }
+        .compute_hash())
     }
 
     /// Append object with a signature into RLP stream
@@ -357,9 +1034,15 @@ impl UnverifiedTransaction {
         s.append(&self.action);
         s.append(&self.value);
         s.append(&self.data);
-        s.append(&self.v);
-        s.append(&self.r);
-        s.append(&self.s);
+        s.append(&self.signature.v);
+        s.append(&self.signature.r);
+        s.append(&self.signature.s);
+    }
+
+    /// Returns the unsigned part and the signature of this transaction, leaving out the cached
+    /// hash. Useful for comparing transactions regardless of whether the hash was computed.
+    pub fn unsigned_and_sig(&self) -> (&Transaction, &SignatureComponents) {
+        (&self.unsigned, &self.signature)
     }
 
     ///	Reference to unsigned part of this transaction.
@@ -369,26 +1052,45 @@ impl UnverifiedTransaction {
 
     /// Returns standardized `v` value (0, 1 or 4 (invalid))
     pub fn standard_v(&self) -> u8 {
-        signature::check_replay_protection(self.v)
+        signature::check_replay_protection(self.signature.v)
     }
 
     /// The `v` value that appears in the RLP.
     pub fn original_v(&self) -> u64 {
-        self.v
+        self.signature.v
     }
 
     /// The chain ID, or `None` if this is a global transaction.
     pub fn chain_id(&self) -> Option<u64> {
-        match self.v {
+        match self.signature.v {
             v if self.is_unsigned() => Some(v),
             v if v >= 35 => Some((v - 35) / 2),
             _ => None,
         }
     }
 
+    /// The chain ID as minimal big-endian bytes, or `None` if this is a global transaction.
+    pub fn chain_id_bytes(&self) -> Option<Vec<u8>> {
+        self.chain_id().map(|chain_id| {
+            let bytes = chain_id.to_be_bytes();
+            let start = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+            bytes[start..].to_vec()
+        })
+    }
+
     /// Construct a signature object from the sig.
     pub fn signature(&self) -> Signature {
-        Signature::from_rsv(&self.r.into(), &self.s.into(), self.standard_v())
+        Signature::from_rsv(
+            &self.signature.r.into(),
+            &self.signature.s.into(),
+            self.standard_v(),
+        )
+    }
+
+    /// Signature in the 65-byte `r || s || v` layout, with `v` being the standardized
+    /// recovery id. A transaction with an invalid `v` yields `4` as the last byte.
+    pub fn signature_bytes(&self) -> [u8; 65] {
+        self.signature().into()
     }
 
     /// Checks whether the signature has a low 's' value.
@@ -405,6 +1107,44 @@ impl UnverifiedTransaction {
         self.hash
     }
 
+    /// `Debug`-like representation with the transaction data replaced by its length,
+    /// so that transactions can be logged without leaking calldata.
+    pub fn redacted_debug(&self) -> String {
+        format!(
+            "UnverifiedTransaction {{ unsigned: Transaction {{ nonce: {:?}, gas_price: {:?}, \
+             gas: {:?}, action: {:?}, value: {:?}, data: <{} bytes> }}, signature: {:?}, \
+             hash: {:?} }}",
+            self.nonce,
+            self.gas_price,
+            self.gas,
+            self.action,
+            self.value,
+            self.data.len(),
+            self.signature,
+            self.hash,
+        )
+    }
+
+    /// Checks if re-signing the transaction with `secret` reproduces its signature, i.e. it was
+    /// signed with a deterministic (RFC 6979) nonce, as `Transaction::sign` does.
+    /// Only useful to holders of the secret.
+    pub fn uses_deterministic_nonce(&self, secret: &Secret) -> bool {
+        match ::ethkey::sign(secret, &self.unsigned.hash(self.chain_id())) {
+            Ok(sig) => self.signature() == sig,
+            Err(_) => false,
+        }
+    }
+
+    /// Checks that the stored hash matches the keccak of the transaction RLP.
+    pub fn verify_hash(&self) -> bool {
+        keccak(&*self.rlp_bytes()) == self.hash
+    }
+
+    /// Short `0x`-prefixed form of the transaction hash (first 4 bytes) for log lines.
+    pub fn short_id(&self) -> String {
+        format!("0x{}", self.hash[..4].to_hex())
+    }
+
     /// Recovers the public key of the sender.
     pub fn recover_public(&self) -> Result<Public, ethkey::Error> {
         Ok(recover(
@@ -413,22 +1153,64 @@ impl UnverifiedTransaction {
         )?)
     }
 
+    /// Cheap pre-check of whether sender recovery is worth attempting: the signature is
+    /// non-empty, `r` and `s` are in range and `v` is valid. Does not attempt the recovery.
+    pub fn is_recoverable(&self) -> bool {
+        self.standard_v() <= 1 && self.rejection_reason(false, self.chain_id()).is_ok()
+    }
+
+    /// Distinct senders recovered with either recovery id, regardless of the one encoded in `v`.
+    /// Helps to identify possible senders of transactions with a corrupt `v`.
+    pub fn recovery_candidates(&self) -> Vec<Address> {
+        let hash = self.unsigned.hash(self.chain_id());
+        let mut candidates = Vec::new();
+        for v in 0..2 {
+            let signature =
+                Signature::from_rsv(&self.signature.r.into(), &self.signature.s.into(), v);
+            if let Ok(public) = recover(&signature, &hash) {
+                let address = public_to_address(&public);
+                if !candidates.contains(&address) {
+                    candidates.push(address);
+                }
+            }
+        }
+        candidates
+    }
+
     /// Verify basic signature params. Does not attempt sender recovery.
     pub fn verify_basic(
         &self,
         check_low_s: bool,
         chain_id: Option<u64>,
     ) -> Result<(), error::Error> {
+        Ok(self.rejection_reason(check_low_s, chain_id)?)
+    }
+
+    /// Verify basic signature params, returning the specific cause on failure.
+    /// Does not attempt sender recovery.
+    pub fn rejection_reason(
+        &self,
+        check_low_s: bool,
+        chain_id: Option<u64>,
+    ) -> Result<(), error::RejectionReason> {
         if self.is_unsigned() {
-            return Err(ethkey::Error::InvalidSignature.into());
+            return Err(error::RejectionReason::Unsigned);
         }
-        if check_low_s {
-            self.check_low_s()?;
+        let curve_order = ethkey::math::curve_order();
+        if self.signature.r.is_zero()
+            || self.signature.s.is_zero()
+            || self.signature.r >= curve_order
+            || self.signature.s >= curve_order
+        {
+            return Err(error::RejectionReason::SignatureOutOfRange);
+        }
+        if check_low_s && self.check_low_s().is_err() {
+            return Err(error::RejectionReason::HighS);
         }
         match (self.chain_id(), chain_id) {
             (None, _) => {}
             (Some(n), Some(m)) if n == m => {}
-            _ => return Err(error::Error::InvalidChainId),
+            _ => return Err(error::RejectionReason::InvalidChainId),
         };
         Ok(())
     }
@@ -492,23 +1274,144 @@ impl SignedTransaction {
         self.public
     }
 
+    /// Checks that the sender matches the cached public key, without recovering it again.
+    /// Returns `false` if the public key is not known.
+    pub fn verify_sender(&self) -> bool {
+        self.public
+            .map_or(false, |public| public_to_address(&public) == self.sender)
+    }
+
     /// Checks is signature is empty.
     pub fn is_unsigned(&self) -> bool {
         self.transaction.is_unsigned()
     }
 
-    /// Deconstructs this transaction back into `UnverifiedTransaction`
-    pub fn deconstruct(self) -> (UnverifiedTransaction, Address, Option<Public>) {
-        (self.transaction, self.sender, self.public)
+    /// Value comparable across transactions for pool ordering only: the effective gas price
+    /// under `base_fee`, or the raw gas price before London (`None`). All transactions of this
+    /// version are legacy ones, for which both are the gas price.
+    pub fn effective_gas_price_for_sort(&self, base_fee: Option<U256>) -> U256 {
+        self.effective_gas_price_opt(base_fee)
     }
-}
 
-/// Signed Transaction that is a part of canon blockchain.
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct LocalizedTransaction {
-    /// Signed part.
-    pub signed: UnverifiedTransaction,
-    /// Block number.
+    /// Sortable key ordering transactions by submission priority: effective gas price under
+    /// `base_fee` descending, then nonce ascending, with the hash as a tie-break.
+    pub fn priority_key(&self, base_fee: Option<U256>) -> (Reverse<U256>, U256, H256) {
+        (
+            Reverse(self.effective_gas_price_for_sort(base_fee)),
+            self.nonce,
+            self.hash(),
+        )
+    }
+
+    /// Score for gossip prioritisation, higher propagates first: the per-gas tip over `base_fee`
+    /// (the full gas price without one) divided by the encoded size in bytes, so that cheap and
+    /// large transactions go last. Saturates at `u64::max_value()`.
+    pub fn gossip_score(&self, base_fee: Option<U256>) -> u64 {
+        let tip = match base_fee {
+            Some(base_fee) => self.gas_price.saturating_sub(base_fee),
+            None => self.gas_price,
+        };
+        let size = U256::from(self.transaction.rlp_bytes().len() as u64);
+        let score = tip / size;
+        if score > U256::from(u64::max_value()) {
+            u64::max_value()
+        } else {
+            score.low_u64()
+        }
+    }
+
+    /// Checks if value and the maximal fee (`gas * gas_price`) add up to exactly `balance`,
+    /// as for "send max" transactions. Returns `false` if the cost overflows.
+    pub fn spends_exactly(&self, balance: U256) -> bool {
+        let (fee, fee_overflow) = self.gas.overflowing_mul(self.gas_price);
+        let (cost, cost_overflow) = self.value.overflowing_add(fee);
+        !fee_overflow && !cost_overflow && cost == balance
+    }
+
+    /// Rejects the transaction if its sender has code deployed (EIP-3607).
+    /// Whether it has is looked up in the state by the caller.
+    pub fn check_sender_has_no_code(&self, sender_has_code: bool) -> Result<(), error::Error> {
+        if sender_has_code {
+            return Err(error::Error::SenderIsContract);
+        }
+        Ok(())
+    }
+
+    /// Checks if this is a plain value transfer from the sender to itself.
+    pub fn is_self_transfer(&self) -> bool {
+        match self.action {
+            Action::Call(to) => to == self.sender && !self.value.is_zero() && self.data.is_empty(),
+            Action::Create => false,
+        }
+    }
+
+    /// Compact encoding for persistence: a version byte, the sender and the transaction RLP.
+    /// Decoded by `UnverifiedTransaction::from_compact_bytes`.
+    pub fn to_compact_bytes(&self) -> Bytes {
+        let mut bytes = vec![COMPACT_FORMAT_VERSION];
+        bytes.extend_from_slice(&self.sender);
+        bytes.extend_from_slice(&self.transaction.rlp_bytes());
+        bytes
+    }
+
+    /// Restores `to_compact_bytes` output using the stored sender, without recovering it from
+    /// the signature. Only for storage written by this node that cannot be tampered with;
+    /// anything else must go through `UnverifiedTransaction::from_compact_bytes` and
+    /// `SignedTransaction::new`. The public key is not stored, so `public_key` returns `None`.
+    pub fn from_compact_bytes_unchecked(bytes: &[u8]) -> Result<Self, DecoderError> {
+        let (transaction, sender) = UnverifiedTransaction::from_compact_bytes(bytes)?;
+        Ok(SignedTransaction {
+            transaction,
+            sender,
+            public: None,
+        })
+    }
+
+    /// Returns a summary of this transaction.
+    pub fn summary(&self) -> TransactionSummary {
+        TransactionSummary {
+            hash: self.hash(),
+            tx_type: 0,
+            from: Some(self.sender),
+            to: self.call_target(),
+            nonce: self.nonce,
+            value: self.value,
+            gas: self.gas,
+        }
+    }
+
+    /// Deconstructs this transaction back into `UnverifiedTransaction`
+    pub fn deconstruct(self) -> (UnverifiedTransaction, Address, Option<Public>) {
+        (self.transaction, self.sender, self.public)
+    }
+}
+
+/// Lightweight view of a transaction for APIs and analytics.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionSummary {
+    /// Transaction hash.
+    pub hash: H256,
+    /// EIP-2718 transaction type, always `0` (legacy) for now.
+    pub tx_type: u8,
+    /// Sender. Always `Some` when built by `SignedTransaction::summary`; optional for views
+    /// of transactions whose sender was not recovered.
+    pub from: Option<Address>,
+    /// Recipient, `None` for contract creation.
+    pub to: Option<Address>,
+    /// Nonce.
+    pub nonce: U256,
+    /// Transferred value.
+    pub value: U256,
+    /// Gas paid up front for transaction execution.
+    pub gas: U256,
+}
+
+/// Signed Transaction that is a part of canon blockchain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalizedTransaction {
+    /// Signed part.
+    pub signed: UnverifiedTransaction,
+    /// Block number.
     pub block_number: BlockNumber,
     /// Block hash.
     pub block_hash: H256,
@@ -533,6 +1436,37 @@ impl LocalizedTransaction {
         self.cached_sender = Some(sender);
         sender
     }
+
+    /// Key ordering transactions chronologically: `(block_number, transaction_index)`.
+    pub fn sort_key(&self) -> (BlockNumber, usize) {
+        (self.block_number, self.transaction_index)
+    }
+}
+
+impl rlp::Encodable for LocalizedTransaction {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(4);
+        s.append(&self.block_number);
+        s.append(&self.block_hash);
+        s.append(&self.transaction_index);
+        s.append(&self.signed);
+    }
+}
+
+impl rlp::Decodable for LocalizedTransaction {
+    fn decode(d: &Rlp) -> Result<Self, DecoderError> {
+        if d.item_count()? != 4 {
+            return Err(DecoderError::RlpIncorrectListLen);
+        }
+        Ok(LocalizedTransaction {
+            block_number: d.val_at(0)?,
+            block_hash: d.val_at(1)?,
+            transaction_index: d.val_at(2)?,
+            signed: d.val_at(3)?,
+            // not serialized, recovered again on demand
+            cached_sender: None,
+        })
+    }
 }
 
 impl Deref for LocalizedTransaction {
@@ -579,6 +1513,117 @@ impl From<SignedTransaction> for PendingTransaction {
     }
 }
 
+/// Signs all transactions with the same secret, preserving their order.
+///
+/// The sender's public key is derived once up front instead of being recovered from
+/// every signature.
+pub fn sign_batch(
+    txs: Vec<Transaction>,
+    secret: &Secret,
+    chain_id: Option<u64>,
+) -> Vec<SignedTransaction> {
+    let key_pair =
+        KeyPair::from_secret(secret.clone()).expect("secret is valid so it has a public key; qed");
+    txs.into_iter()
+        .map(|tx| {
+            let sig = ethkey::sign(secret, &tx.hash(chain_id))
+                .expect("data is valid and context has signing capabilities; qed");
+            SignedTransaction {
+                transaction: tx.with_signature(sig, chain_id),
+                sender: key_pair.address(),
+                public: Some(*key_pair.public()),
+            }
+        })
+        .collect()
+}
+
+/// Runs `verify_basic` on all transactions of a block, stopping at the first invalid one.
+/// Returns the index and the error of that transaction.
+pub fn verify_block_transactions(
+    txs: &[UnverifiedTransaction],
+    check_low_s: bool,
+    chain_id: Option<u64>,
+) -> Result<(), (usize, error::Error)> {
+    for (index, tx) in txs.iter().enumerate() {
+        tx.verify_basic(check_low_s, chain_id)
+            .map_err(|e| (index, e))?;
+    }
+    Ok(())
+}
+
+/// Checks if the transaction lacks EIP-155 replay protection and could therefore be replayed
+/// on any chain.
+pub fn is_cross_chain_replayable(tx: &UnverifiedTransaction) -> bool {
+    tx.chain_id().is_none()
+}
+
+/// Checks if two RLP-encoded transactions are the same transaction, even if one carries the
+/// malleated signature (`s` replaced with `n - s` and the recovery id flipped) of the other.
+pub fn same_transaction(raw_a: &[u8], raw_b: &[u8]) -> Result<bool, DecoderError> {
+    let a: UnverifiedTransaction = rlp::decode(raw_a)?;
+    let b: UnverifiedTransaction = rlp::decode(raw_b)?;
+    Ok(a.unsigned == b.unsigned
+        && a.chain_id() == b.chain_id()
+        && canonical_signature(&a) == canonical_signature(&b))
+}
+
+/// Signature of the transaction as `(standard_v, r, s)`, with `s` moved to the lower half of
+/// the curve order.
+fn canonical_signature(tx: &UnverifiedTransaction) -> (u8, U256, U256) {
+    let curve_order = ethkey::math::curve_order();
+    let (v, r, s) = (tx.standard_v(), tx.signature.r, tx.signature.s);
+    if s > curve_order / U256::from(2) && s < curve_order {
+        (v ^ 1, r, curve_order - s)
+    } else {
+        (v, r, s)
+    }
+}
+
+/// Hashes of given transactions, in order. The hashes are cached when a transaction is decoded
+/// or signed, so this does no hashing itself.
+pub fn compute_hashes(txs: &[UnverifiedTransaction]) -> Vec<H256> {
+    txs.iter().map(UnverifiedTransaction::hash).collect()
+}
+
+/// Type of an encoded transaction, read from its first byte without decoding the rest:
+/// `0` for a legacy transaction (an RLP list), otherwise the EIP-2718 type byte (`0x00..=0x7f`).
+/// Only legacy transactions can be decoded by this version.
+pub fn peek_tx_type(raw: &[u8]) -> Result<u8, DecoderError> {
+    match raw.first() {
+        None => Err(DecoderError::RlpIsTooShort),
+        Some(&first) if first >= 0xc0 => Ok(0),
+        Some(&first) if first <= 0x7f => Ok(first),
+        Some(_) => Err(DecoderError::RlpExpectedToBeList),
+    }
+}
+
+/// Key of the transaction at given index in the block transactions trie.
+pub fn transaction_trie_key(index: usize) -> Vec<u8> {
+    rlp::encode(&index).into_vec()
+}
+
+/// Lazily decodes transactions from the RLP of a block body, one at a time.
+///
+/// If the body itself is malformed, a single error is yielded.
+pub fn iter_block_transactions<'a>(
+    body: &'a [u8],
+) -> impl Iterator<Item = Result<UnverifiedTransaction, DecoderError>> + 'a {
+    let transactions = Rlp::new(body).at(0).and_then(|transactions| {
+        let count = transactions.item_count()?;
+        Ok((transactions, count))
+    });
+    let (error, transactions) = match transactions {
+        Ok(transactions) => (None, Some(transactions)),
+        Err(e) => (Some(Err(e)), None),
+    };
+
+    error
+        .into_iter()
+        .chain(transactions.into_iter().flat_map(|(transactions, count)| {
+            (0..count).map(move |i| transactions.val_at::<UnverifiedTransaction>(i))
+        }))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -718,6 +1763,20 @@ mod tests {
         assert_eq!(t.chain_id(), Some(69));
     }
 
+    #[test]
+    fn should_compute_calldata_gas() {
+        let with_data = |data: Vec<u8>| Transaction {
+            data,
+            ..Default::default()
+        };
+
+        assert_eq!(with_data(vec![]).calldata_gas(4, 16), 0);
+        assert_eq!(with_data(vec![0; 10]).calldata_gas(4, 16), 40);
+        assert_eq!(with_data(vec![0xff; 10]).calldata_gas(4, 16), 160);
+        assert_eq!(with_data(vec![0, 1, 0, 2, 3]).calldata_gas(4, 16), 56);
+        assert_eq!(with_data(vec![0, 1, 0, 2, 3]).calldata_gas(4, 68), 212);
+    }
+
     #[test]
     fn should_agree_with_vitalik() {
         use rustc_hex::FromHex;
@@ -741,4 +1800,1635 @@ mod tests {
         test_vector("f867088504a817c8088302e2489435353535353535353535353535353535353535358202008025a064b1702d9298fee62dfeccc57d322a463ad55ca201256d01f62b45b2e1c21c12a064b1702d9298fee62dfeccc57d322a463ad55ca201256d01f62b45b2e1c21c10", "0x9bddad43f934d313c2b79ca28a432dd2b7281029");
         test_vector("f867098504a817c809830334509435353535353535353535353535353535353535358202d98025a052f8f61201b2b11a78d6e866abc9c3db2ae8631fa656bfe5cb53668255367afba052f8f61201b2b11a78d6e866abc9c3db2ae8631fa656bfe5cb53668255367afb", "0x3c24d7329e92f84f08556ceb6df1cdb0104ca49f");
     }
+
+    #[test]
+    fn should_check_base_fee_against_gas_price() {
+        let t = Transaction {
+            gas_price: U256::from(100),
+            ..Default::default()
+        };
+
+        assert!(t.can_pay_base_fee(U256::from(99)));
+        assert!(t.can_pay_base_fee(U256::from(100)));
+        assert!(!t.can_pay_base_fee(U256::from(101)));
+    }
+
+    #[test]
+    fn should_compute_effective_gas_price_with_and_without_base_fee() {
+        let t = Transaction {
+            gas_price: U256::from(100),
+            ..Default::default()
+        };
+        assert_eq!(t.effective_gas_price_opt(None), U256::from(100));
+        assert_eq!(
+            t.effective_gas_price_opt(Some(U256::zero())),
+            U256::from(100)
+        );
+        assert_eq!(
+            t.effective_gas_price_opt(Some(U256::from(40))),
+            U256::from(100)
+        );
+        assert_eq!(
+            t.effective_gas_price_opt(Some(U256::from(400))),
+            U256::from(100)
+        );
+        assert_eq!(
+            t.effective_gas_price_opt(Some(U256::max_value())),
+            U256::from(100)
+        );
+    }
+
+    #[test]
+    fn should_compute_burned_base_fee() {
+        let t = Transaction {
+            gas_price: U256::from(100),
+            ..Default::default()
+        };
+        let (base_fee, gas_used) = (U256::from(40), U256::from(21_000));
+        assert_eq!(t.burns_base_fee(base_fee, gas_used), U256::from(840_000));
+        assert_eq!(
+            t.burns_base_fee(base_fee, gas_used) + t.miner_reward(base_fee, gas_used),
+            t.gas_price * gas_used
+        );
+        assert_eq!(
+            t.burns_base_fee(U256::max_value(), gas_used),
+            U256::max_value()
+        );
+    }
+
+    #[test]
+    fn should_report_no_access_list_for_legacy_transactions() {
+        let t = Transaction {
+            action: Action::Call(Address::from(0x69)),
+            data: vec![0x11; 32],
+            ..Default::default()
+        };
+        assert!(!t.has_access_list());
+        assert_eq!(t.access_list_len(), 0);
+    }
+
+    #[test]
+    fn should_report_legacy_transactions() {
+        assert!(Transaction::default().is_legacy());
+        let signed = Transaction {
+            action: Action::Call(Address::from(0x69)),
+            ..Default::default()
+        }
+        .null_sign(1);
+        assert!(signed.is_legacy());
+    }
+
+    #[test]
+    fn should_sort_by_effective_gas_price() {
+        let t = |gas_price: u64| {
+            Transaction {
+                gas_price: U256::from(gas_price),
+                ..Default::default()
+            }
+            .fake_sign(Address::from(0x69))
+        };
+        let mut txs = vec![t(20), t(5), t(50)];
+
+        for base_fee in &[None, Some(U256::from(10))] {
+            txs.sort_by(|a, b| {
+                b.effective_gas_price_for_sort(*base_fee)
+                    .cmp(&a.effective_gas_price_for_sort(*base_fee))
+            });
+            let order: Vec<_> = txs.iter().map(|tx| tx.gas_price.low_u64()).collect();
+            assert_eq!(order, vec![50, 20, 5]);
+            assert_eq!(
+                txs[0].effective_gas_price_for_sort(*base_fee),
+                txs[0].gas_price
+            );
+        }
+    }
+
+    #[test]
+    fn should_name_malformed_field_in_decoder_error() {
+        let mut s = RlpStream::new_list(9);
+        s.append(&U256::zero());
+        s.begin_list(0);
+        s.append(&U256::from(21000));
+        s.append(&Action::Create);
+        s.append(&U256::zero());
+        s.append(&Vec::<u8>::new());
+        s.append(&27u8);
+        s.append(&U256::one());
+        s.append(&U256::one());
+
+        let res: Result<UnverifiedTransaction, DecoderError> = rlp::decode(&s.out());
+        assert_eq!(
+            res,
+            Err(DecoderError::Custom(
+                "Invalid transaction field: gas_price (expected data)"
+            ))
+        );
+    }
+
+    #[test]
+    fn should_round_trip_raw_hex() {
+        let raw = "0xf85f800182520894095e7baea6a6c7c4c2dfeb977efac326af552d870a801ba048b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353a0efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804";
+
+        let t = UnverifiedTransaction::from_hex(raw).unwrap();
+        assert_eq!(t.to_hex(), raw);
+        assert_eq!(
+            UnverifiedTransaction::from_hex(&format!("  {}\n", &raw[2..])),
+            Ok(t)
+        );
+        assert!(UnverifiedTransaction::from_hex("0xzz").is_err());
+    }
+
+    #[test]
+    fn should_encode_transaction_trie_key() {
+        assert_eq!(transaction_trie_key(0), vec![0x80]);
+        assert_eq!(transaction_trie_key(1), vec![0x01]);
+        assert_eq!(transaction_trie_key(127), vec![0x7f]);
+        assert_eq!(transaction_trie_key(128), vec![0x81, 0x80]);
+    }
+
+    #[test]
+    fn should_report_rejection_reason() {
+        use ethkey::{Generator, Random};
+
+        let key = Random.generate().unwrap();
+        let curve_order = ethkey::math::curve_order();
+        let t = Transaction {
+            action: Action::Create,
+            nonce: U256::from(42),
+            gas_price: U256::from(3000),
+            gas: U256::from(50_000),
+            value: U256::from(1),
+            data: b"Hello!".to_vec(),
+        };
+        let with_rs = |r: U256, s: U256| UnverifiedTransaction {
+            unsigned: t.clone(),
+            signature: SignatureComponents { v: 27, r, s },
+            hash: 0.into(),
+        };
+
+        let signed = t.clone().sign(&key.secret(), Some(69));
+        assert_eq!(signed.rejection_reason(true, Some(69)), Ok(()));
+        assert_eq!(
+            signed.rejection_reason(true, Some(1)),
+            Err(error::RejectionReason::InvalidChainId)
+        );
+        assert_eq!(
+            t.clone().null_sign(1).rejection_reason(false, Some(1)),
+            Err(error::RejectionReason::Unsigned)
+        );
+        assert_eq!(
+            with_rs(curve_order, U256::one()).rejection_reason(false, None),
+            Err(error::RejectionReason::SignatureOutOfRange)
+        );
+        assert_eq!(
+            with_rs(U256::one(), curve_order - U256::one()).rejection_reason(true, None),
+            Err(error::RejectionReason::HighS)
+        );
+        assert_eq!(
+            with_rs(U256::one(), curve_order - U256::one()).rejection_reason(false, None),
+            Ok(())
+        );
+
+        assert_eq!(
+            signed.verify_basic(true, Some(1)),
+            Err(error::Error::InvalidChainId)
+        );
+        assert_eq!(
+            t.null_sign(1).verify_basic(false, Some(1)),
+            Err(error::Error::Rejected(error::RejectionReason::Unsigned))
+        );
+    }
+
+    #[test]
+    fn should_compare_unsigned_and_signature_regardless_of_hash() {
+        let bytes = ::rustc_hex::FromHex::from_hex("f85f800182520894095e7baea6a6c7c4c2dfeb977efac326af552d870a801ba048b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353a0efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804").unwrap();
+        let t: UnverifiedTransaction = rlp::decode(&bytes).unwrap();
+        let t_new: UnverifiedTransaction = rlp::decode(&rlp::encode(&t)).unwrap();
+        assert_eq!(t_new.unsigned_and_sig(), t.unsigned_and_sig());
+        assert_eq!(t_new.hash(), t.hash());
+
+        let without_hash = UnverifiedTransaction {
+            hash: 0.into(),
+            ..t.clone()
+        };
+        assert_ne!(without_hash, t);
+        assert_eq!(without_hash.unsigned_and_sig(), t.unsigned_and_sig());
+    }
+
+    #[test]
+    fn should_sign_only_for_matching_hash() {
+        use ethkey::{Generator, Random};
+
+        let key = Random.generate().unwrap();
+        let t = Transaction {
+            action: Action::Create,
+            nonce: U256::from(42),
+            gas_price: U256::from(3000),
+            gas: U256::from(50_000),
+            value: U256::from(1),
+            data: b"Hello!".to_vec(),
+        };
+        let hash = t.hash(Some(69));
+        let sig = ::ethkey::sign(&key.secret(), &hash).unwrap();
+
+        let u = t
+            .clone()
+            .with_signature_for_hash(sig.clone(), Some(69), hash)
+            .unwrap();
+        assert_eq!(
+            SignedTransaction::new(u).unwrap().sender(),
+            Address::from(keccak(key.public()))
+        );
+
+        let wrong_hash = t.hash(Some(1));
+        assert_eq!(
+            t.with_signature_for_hash(sig, Some(69), wrong_hash),
+            Err(error::Error::SigningHashMismatch {
+                expected: wrong_hash,
+                computed: hash,
+            })
+        );
+    }
+
+    #[test]
+    fn should_check_gas_against_block_gas_limit() {
+        let t = Transaction {
+            gas: U256::from(21000),
+            ..Default::default()
+        };
+
+        assert_eq!(t.check_within_block_gas_limit(U256::from(21000)), Ok(()));
+        assert_eq!(
+            t.check_within_block_gas_limit(U256::from(20999)),
+            Err(error::Error::GasLimitExceeded {
+                limit: U256::from(20999),
+                got: U256::from(21000),
+            })
+        );
+    }
+
+    #[test]
+    fn should_format_short_id() {
+        let t = UnverifiedTransaction::from_hex("0xf85f800182520894095e7baea6a6c7c4c2dfeb977efac326af552d870a801ba048b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353a0efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804").unwrap();
+        assert_eq!(
+            t.hash(),
+            "41df922fd0d4766fcc02e161f8295ec28522f329ae487f14d811e4b64c8d6e31".into()
+        );
+        assert_eq!(t.short_id(), "0x41df922f");
+    }
+
+    #[test]
+    fn should_detect_self_transfer() {
+        let sender = Address::from(0x69);
+        let t = |action: Action, data: Vec<u8>| {
+            Transaction {
+                action,
+                value: U256::from(1),
+                data,
+                ..Default::default()
+            }
+            .fake_sign(sender)
+        };
+
+        assert!(t(Action::Call(sender), vec![]).is_self_transfer());
+        assert!(!t(Action::Call(Address::from(0x70)), vec![]).is_self_transfer());
+        assert!(!t(Action::Call(sender), vec![1]).is_self_transfer());
+        assert!(!t(Action::Create, vec![]).is_self_transfer());
+    }
+
+    #[test]
+    fn should_compute_intrinsic_gas_per_schedule() {
+        let create = Transaction {
+            action: Action::Create,
+            data: vec![0, 1],
+            ..Default::default()
+        };
+        let call = Transaction {
+            action: Action::Call(Address::from(0x69)),
+            ..create.clone()
+        };
+
+        assert_eq!(create.intrinsic_gas(&GasSchedule::frontier()), 21072);
+        assert_eq!(create.intrinsic_gas(&GasSchedule::homestead()), 53072);
+        assert_eq!(create.intrinsic_gas(&GasSchedule::istanbul()), 53020);
+        assert_eq!(call.intrinsic_gas(&GasSchedule::frontier()), 21072);
+        assert_eq!(call.intrinsic_gas(&GasSchedule::homestead()), 21072);
+        assert_eq!(call.intrinsic_gas(&GasSchedule::istanbul()), 21020);
+    }
+
+    #[test]
+    fn should_iterate_block_transactions() {
+        let t = |nonce: u64| {
+            Transaction {
+                nonce: U256::from(nonce),
+                ..Default::default()
+            }
+            .fake_sign(Address::from(0x69))
+        };
+        let mut body = RlpStream::new_list(2);
+        body.begin_list(3);
+        body.append(&t(0));
+        body.append(&t(1));
+        body.append(&t(2));
+        body.begin_list(0);
+        let body = body.out();
+
+        let mut count = 0;
+        for (i, tx) in iter_block_transactions(&body).enumerate() {
+            assert_eq!(tx.unwrap().nonce, U256::from(i as u64));
+            count += 1;
+        }
+        assert_eq!(count, 3);
+
+        let mut broken = iter_block_transactions(&[0x01]);
+        assert!(broken.next().unwrap().is_err());
+        assert!(broken.next().is_none());
+    }
+
+    #[test]
+    fn should_redact_data_in_debug_output() {
+        let t = Transaction {
+            data: b"secret calldata".to_vec(),
+            ..Default::default()
+        }
+        .fake_sign(Address::from(0x69));
+
+        let redacted = t.redacted_debug();
+        assert!(redacted.contains("data: <15 bytes>"));
+        assert!(redacted.contains(&format!("hash: {:?}", t.hash())));
+        assert!(!redacted.contains(&format!("{:?}", t.data)));
+        assert!(format!("{:?}", *t).contains(&format!("{:?}", t.data)));
+    }
+
+    #[test]
+    fn should_sort_by_priority_key() {
+        let t = |gas_price: u64, nonce: u64| {
+            Transaction {
+                gas_price: U256::from(gas_price),
+                nonce: U256::from(nonce),
+                ..Default::default()
+            }
+            .fake_sign(Address::from(0x69))
+        };
+        let mut txs = vec![t(10, 1), t(20, 3), t(10, 0), t(20, 2), t(5, 0)];
+
+        for base_fee in &[None, Some(U256::from(7))] {
+            txs.sort_by_key(|tx| tx.priority_key(*base_fee));
+            let order: Vec<_> = txs
+                .iter()
+                .map(|tx| (tx.gas_price.low_u64(), tx.nonce.low_u64()))
+                .collect();
+            assert_eq!(order, vec![(20, 2), (20, 3), (10, 0), (10, 1), (5, 0)]);
+        }
+    }
+
+    #[test]
+    fn should_verify_sender_against_cached_public() {
+        use ethkey::{Generator, Random};
+
+        let key = Random.generate().unwrap();
+        let t = Transaction::default().sign(&key.secret(), None);
+        assert!(t.verify_sender());
+
+        let corrupted = SignedTransaction {
+            sender: Address::from(0x69),
+            ..t.clone()
+        };
+        assert!(!corrupted.verify_sender());
+
+        let without_public = SignedTransaction { public: None, ..t };
+        assert!(!without_public.verify_sender());
+    }
+
+    #[test]
+    fn should_enforce_max_gas_price() {
+        let t = Transaction {
+            gas_price: U256::from(100),
+            ..Default::default()
+        };
+
+        assert_eq!(t.verify_limits(None), Ok(()));
+        assert_eq!(t.verify_limits(Some(U256::from(100))), Ok(()));
+        assert_eq!(
+            t.verify_limits(Some(U256::from(99))),
+            Err(error::Error::GasPriceTooHigh {
+                limit: U256::from(99),
+                got: U256::from(100),
+            })
+        );
+    }
+
+    #[test]
+    fn should_compute_signature_independent_idempotency_key() {
+        use ethkey::{Generator, Random};
+
+        let key = Random.generate().unwrap();
+        let sender = key.address();
+        let t = Transaction {
+            action: Action::Create,
+            nonce: U256::from(42),
+            gas_price: U256::from(3000),
+            gas: U256::from(50_000),
+            value: U256::from(1),
+            data: b"Hello!".to_vec(),
+        };
+        let key_of = |tx: &SignedTransaction| tx.idempotency_key(tx.sender());
+
+        let signed = t.clone().sign(&key.secret(), None);
+        let signed_for_chain = t.clone().sign(&key.secret(), Some(69));
+        assert_ne!(signed.signature(), signed_for_chain.signature());
+        assert_eq!(key_of(&signed), t.idempotency_key(sender));
+        assert_eq!(key_of(&signed_for_chain), t.idempotency_key(sender));
+        assert_eq!(
+            key_of(&t.clone().fake_sign(sender)),
+            t.idempotency_key(sender)
+        );
+        assert_ne!(
+            t.idempotency_key(sender),
+            t.idempotency_key(Address::from(0x69))
+        );
+    }
+
+    #[test]
+    fn should_encode_decode_localized_transaction() {
+        use ethkey::{Generator, Random};
+
+        let key = Random.generate().unwrap();
+        let signed = Transaction::default().sign(&key.secret(), Some(69));
+        let localized = LocalizedTransaction {
+            signed: signed.into(),
+            block_number: 1024,
+            block_hash: H256::from(0x42),
+            transaction_index: 7,
+            cached_sender: Some(key.address()),
+        };
+
+        let mut decoded: LocalizedTransaction = rlp::decode(&rlp::encode(&localized)).unwrap();
+        assert_eq!(decoded.cached_sender, None);
+        assert_eq!(
+            decoded,
+            LocalizedTransaction {
+                cached_sender: None,
+                ..localized.clone()
+            }
+        );
+        assert_eq!(decoded.sender(), key.address());
+    }
+
+    #[test]
+    fn should_detect_nonce_bump() {
+        let bump = Transaction {
+            action: Action::Call(Address::from(0x69)),
+            ..Default::default()
+        };
+
+        assert!(bump.is_nonce_bump());
+        assert!(!Transaction {
+            value: U256::from(1),
+            ..bump.clone()
+        }
+        .is_nonce_bump());
+        assert!(!Transaction {
+            data: vec![1],
+            ..bump.clone()
+        }
+        .is_nonce_bump());
+        assert!(!Transaction {
+            action: Action::Create,
+            ..bump
+        }
+        .is_nonce_bump());
+    }
+
+    #[test]
+    fn should_return_signature_bytes() {
+        let t = UnverifiedTransaction::from_hex("0xf85f800182520894095e7baea6a6c7c4c2dfeb977efac326af552d870a801ba048b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353a0efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804").unwrap();
+        let r: Vec<u8> = "48b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353"
+            .from_hex()
+            .unwrap();
+        let s: Vec<u8> = "efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804"
+            .from_hex()
+            .unwrap();
+
+        let bytes = t.signature_bytes();
+        assert_eq!(&bytes[0..32], &r[..]);
+        assert_eq!(&bytes[32..64], &s[..]);
+        assert_eq!(bytes[64], 0);
+    }
+
+    #[test]
+    fn should_detect_simple_transfer() {
+        let transfer = Transaction {
+            action: Action::Call(Address::from(0x69)),
+            value: U256::from(1),
+            ..Default::default()
+        };
+
+        assert!(transfer.is_simple_transfer());
+        assert!(!Transaction {
+            data: vec![0xa9, 0x05, 0x9c, 0xbb],
+            ..transfer.clone()
+        }
+        .is_simple_transfer());
+        assert!(!Transaction {
+            action: Action::Create,
+            ..transfer
+        }
+        .is_simple_transfer());
+    }
+
+    #[test]
+    fn should_reject_nonce_too_far_in_future() {
+        let t = Transaction {
+            nonce: U256::from(20),
+            ..Default::default()
+        };
+
+        assert_eq!(t.check_nonce_sane(U256::from(10), 10), Ok(()));
+        assert_eq!(
+            t.check_nonce_sane(U256::from(10), 9),
+            Err(error::Error::NonceTooFarInFuture {
+                limit: U256::from(19),
+                got: U256::from(20),
+            })
+        );
+        assert_eq!(t.check_nonce_sane(U256::max_value(), 1), Ok(()));
+    }
+
+    #[test]
+    fn should_return_address_bloom_inputs() {
+        let sender = Address::from(0x69);
+        let call = Transaction {
+            action: Action::Call(Address::from(0x70)),
+            ..Default::default()
+        };
+        let create = Transaction::default();
+
+        assert_eq!(
+            call.address_bloom_inputs(sender),
+            vec![sender, Address::from(0x70)]
+        );
+        assert_eq!(create.address_bloom_inputs(sender), vec![sender]);
+    }
+
+    #[test]
+    fn should_detect_exact_balance_spend() {
+        let t = |value: U256| {
+            Transaction {
+                gas: U256::from(21000),
+                gas_price: U256::from(10),
+                value,
+                ..Default::default()
+            }
+            .fake_sign(Address::from(0x69))
+        };
+
+        assert!(t(U256::from(790_000)).spends_exactly(U256::from(1_000_000)));
+        assert!(!t(U256::from(500_000)).spends_exactly(U256::from(1_000_000)));
+        assert!(!t(U256::max_value()).spends_exactly(U256::max_value()));
+    }
+
+    #[test]
+    fn should_distinguish_create_from_call_to_zero_address() {
+        let mut call_to_zero = vec![0x94];
+        call_to_zero.extend_from_slice(&[0u8; 20]);
+
+        let action: Action = rlp::decode(&[0x80]).unwrap();
+        assert_eq!(action, Action::Create);
+        let action: Action = rlp::decode(&call_to_zero).unwrap();
+        assert_eq!(action, Action::Call(H160::zero()));
+
+        assert_eq!(&*rlp::encode(&Action::Create), &[0x80][..]);
+        assert_eq!(
+            &*rlp::encode(&Action::Call(H160::zero())),
+            &call_to_zero[..]
+        );
+        for action in vec![Action::Create, Action::Call(H160::zero())] {
+            let decoded: Action = rlp::decode(&rlp::encode(&action)).unwrap();
+            assert_eq!(decoded, action);
+        }
+    }
+
+    #[test]
+    fn should_detect_tampered_hash() {
+        let t = Transaction::default()
+            .fake_sign(Address::from(0x69))
+            .deconstruct()
+            .0;
+        assert!(t.verify_hash());
+
+        let tampered = UnverifiedTransaction {
+            hash: H256::from(0x42),
+            ..t
+        };
+        assert!(!tampered.verify_hash());
+    }
+
+    #[test]
+    fn should_sign_batch_in_order() {
+        use ethkey::{Generator, Random};
+
+        let key = Random.generate().unwrap();
+        let txs = (0..5u64)
+            .map(|nonce| Transaction {
+                nonce: U256::from(nonce),
+                ..Default::default()
+            })
+            .collect();
+
+        let signed = sign_batch(txs, key.secret(), Some(69));
+        assert_eq!(signed.len(), 5);
+        for (nonce, tx) in signed.into_iter().enumerate() {
+            assert_eq!(tx.nonce, U256::from(nonce as u64));
+            assert_eq!(tx.chain_id(), Some(69));
+            assert_eq!(tx.sender(), key.address());
+            let recovered = SignedTransaction::new(tx.clone().into()).unwrap();
+            assert_eq!(recovered, tx);
+        }
+    }
+
+    #[test]
+    fn should_compute_miner_reward() {
+        let t = Transaction {
+            gas_price: U256::from(30),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            t.miner_reward(U256::from(10), U256::from(21000)),
+            U256::from(420_000)
+        );
+        assert_eq!(
+            t.miner_reward(U256::from(40), U256::from(21000)),
+            U256::zero()
+        );
+    }
+
+    #[test]
+    fn should_decode_erc20_transfer() {
+        let t = |data: &str| Transaction {
+            action: Action::Call(Address::from(0x69)),
+            data: data.from_hex().unwrap(),
+            ..Default::default()
+        };
+
+        let transfer = t("a9059cbb000000000000000000000000095e7baea6a6c7c4c2dfeb977efac326af552d870000000000000000000000000000000000000000000000000de0b6b3a7640000");
+        assert_eq!(
+            transfer.decoded_erc20_transfer(),
+            Some((
+                "095e7baea6a6c7c4c2dfeb977efac326af552d87".into(),
+                U256::from(1_000_000_000_000_000_000u64)
+            ))
+        );
+
+        let approve = t("095ea7b3000000000000000000000000095e7baea6a6c7c4c2dfeb977efac326af552d870000000000000000000000000000000000000000000000000de0b6b3a7640000");
+        assert_eq!(approve.decoded_erc20_transfer(), None);
+        assert_eq!(t("a9059cbb").decoded_erc20_transfer(), None);
+        assert_eq!(
+            Transaction {
+                action: Action::Create,
+                ..transfer
+            }
+            .decoded_erc20_transfer(),
+            None
+        );
+    }
+
+    #[test]
+    fn should_compute_cost_after_capped_refund() {
+        let t = Transaction {
+            gas_price: U256::from(10),
+            value: U256::from(1000),
+            ..Default::default()
+        };
+
+        // refund below the cap of 10_000 / 5 = 2_000
+        assert_eq!(
+            t.cost_after_max_refund(U256::from(10_000), U256::from(1_500)),
+            U256::from(86_000)
+        );
+        // refund above the cap
+        assert_eq!(
+            t.cost_after_max_refund(U256::from(10_000), U256::from(5_000)),
+            U256::from(81_000)
+        );
+    }
+
+    #[test]
+    fn should_return_data_len() {
+        let t = Transaction {
+            data: b"Hello!".to_vec(),
+            ..Default::default()
+        }
+        .fake_sign(Address::from(0x69));
+
+        assert_eq!(t.data_len(), 6);
+        assert_eq!(Transaction::default().data_len(), 0);
+    }
+
+    #[test]
+    fn should_verify_block_transactions_until_first_invalid() {
+        use ethkey::{Generator, Random};
+
+        let key = Random.generate().unwrap();
+        let t = |nonce: u64, chain_id: Option<u64>| -> UnverifiedTransaction {
+            Transaction {
+                nonce: U256::from(nonce),
+                ..Default::default()
+            }
+            .sign(&key.secret(), chain_id)
+            .into()
+        };
+
+        let valid = vec![t(0, Some(69)), t(1, None), t(2, Some(69))];
+        assert_eq!(verify_block_transactions(&valid, true, Some(69)), Ok(()));
+
+        let invalid = vec![t(0, Some(69)), t(1, Some(1)), t(2, Some(2))];
+        assert_eq!(
+            verify_block_transactions(&invalid, true, Some(69)),
+            Err((1, error::Error::InvalidChainId))
+        );
+    }
+
+    #[test]
+    fn should_detect_large_calldata() {
+        let t = Transaction {
+            data: vec![0; 128],
+            ..Default::default()
+        };
+
+        assert!(t.is_large_calldata(127));
+        assert!(!t.is_large_calldata(128));
+        assert!(!t.is_large_calldata(129));
+    }
+
+    #[test]
+    fn should_construct_from_fields() {
+        let t = Transaction::from_fields(
+            U256::from(42),
+            U256::from(3000),
+            U256::from(50_000),
+            Action::Call(Address::from(0x69)),
+            U256::from(1),
+            b"Hello!".to_vec(),
+        );
+
+        assert_eq!(
+            t,
+            Transaction {
+                nonce: U256::from(42),
+                gas_price: U256::from(3000),
+                gas: U256::from(50_000),
+                action: Action::Call(Address::from(0x69)),
+                value: U256::from(1),
+                data: b"Hello!".to_vec(),
+            }
+        );
+    }
+
+    #[test]
+    fn should_encode_decode_standalone_signature() {
+        let t = UnverifiedTransaction::from_hex("0xf85f800182520894095e7baea6a6c7c4c2dfeb977efac326af552d870a801ba048b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353a0efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804").unwrap();
+        let (_, signature) = t.unsigned_and_sig();
+
+        let encoded = signature.encode_standalone();
+        assert_eq!(
+            SignatureComponents::decode_standalone(&encoded),
+            Ok(signature.clone())
+        );
+        assert_eq!(
+            SignatureComponents::decode_standalone(&[0xc0]),
+            Err(DecoderError::RlpIncorrectListLen)
+        );
+    }
+
+    #[test]
+    fn should_compute_total_debit() {
+        let t = Transaction {
+            gas_price: U256::from(10),
+            value: U256::from(1000),
+            ..Default::default()
+        };
+
+        assert_eq!(t.total_debit(None, U256::from(21000)), U256::from(211_000));
+        assert_eq!(t.total_debit(None, U256::zero()), U256::from(1000));
+        assert_eq!(
+            t.total_debit(Some(U256::from(5)), U256::from(21000)),
+            U256::from(211_000)
+        );
+    }
+
+    #[test]
+    fn should_extract_create2_args() {
+        let selector = [0x4a, 0xf6, 0x3f, 0x02];
+        let salt = H256::from(0x42);
+        let init_code = vec![0x60, 0x00, 0x60, 0x00, 0xf3];
+        let mut data = selector.to_vec();
+        data.extend_from_slice(&salt);
+        data.extend_from_slice(&H256::from(0x40));
+        data.extend_from_slice(&H256::from(init_code.len() as u64));
+        data.extend_from_slice(&init_code);
+        data.resize(4 + 4 * 32, 0);
+        let t = Transaction {
+            action: Action::Call(Address::from(0x69)),
+            data,
+            ..Default::default()
+        };
+
+        assert_eq!(t.create2_args(|s| s == selector), Some((salt, init_code)));
+        assert_eq!(t.create2_args(|_| false), None);
+        assert_eq!(
+            Transaction {
+                data: t.data[..68].to_vec(),
+                ..t.clone()
+            }
+            .create2_args(|s| s == selector),
+            None
+        );
+        assert_eq!(
+            Transaction {
+                action: Action::Create,
+                ..t
+            }
+            .create2_args(|s| s == selector),
+            None
+        );
+    }
+
+    #[test]
+    fn should_compute_tip_percent_over_base_fee() {
+        let t = |gas_price: U256| Transaction {
+            gas_price,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            t(U256::from(150)).tip_percent_over_base(U256::from(100)),
+            Some(50)
+        );
+        assert_eq!(
+            t(U256::from(100)).tip_percent_over_base(U256::from(100)),
+            Some(0)
+        );
+        assert_eq!(
+            t(U256::from(50)).tip_percent_over_base(U256::from(100)),
+            Some(0)
+        );
+        assert_eq!(
+            t(U256::from(300)).tip_percent_over_base(U256::from(100)),
+            Some(200)
+        );
+        assert_eq!(t(U256::from(150)).tip_percent_over_base(U256::zero()), None);
+        assert_eq!(
+            t(U256::max_value()).tip_percent_over_base(U256::one()),
+            Some(u64::max_value())
+        );
+    }
+
+    #[test]
+    fn should_reject_non_canonical_v_in_strict_decode() {
+        let with_v = |v: u64| {
+            rlp::encode(&UnverifiedTransaction {
+                unsigned: Transaction::default(),
+                signature: SignatureComponents {
+                    v,
+                    r: U256::one(),
+                    s: U256::one(),
+                },
+                hash: 0.into(),
+            })
+        };
+
+        for v in &[0, 1, 26, 29, 34] {
+            assert_eq!(
+                UnverifiedTransaction::decode_strict(&with_v(*v)),
+                Err(DecoderError::Custom("Invalid transaction field: v"))
+            );
+            assert!(rlp::decode::<UnverifiedTransaction>(&with_v(*v)).is_ok());
+        }
+        for v in &[27, 28, 35, 36, 173] {
+            assert!(UnverifiedTransaction::decode_strict(&with_v(*v)).is_ok());
+        }
+    }
+
+    #[test]
+    fn should_build_transaction_for_estimation() {
+        let from = Address::from(0x69);
+        let t = Transaction::for_estimation(
+            from,
+            Action::Call(Address::from(0x70)),
+            U256::from(1),
+            b"Hello!".to_vec(),
+            U256::from(50_000_000),
+        );
+
+        assert_eq!(t.sender(), from);
+        assert_eq!(t.gas, U256::from(50_000_000));
+        assert_eq!(t.gas_price, U256::zero());
+        assert_eq!(t.action, Action::Call(Address::from(0x70)));
+        assert_eq!(t.data, b"Hello!".to_vec());
+    }
+
+    #[test]
+    fn should_return_call_target() {
+        let call = Transaction {
+            action: Action::Call(Address::from(0x69)),
+            ..Default::default()
+        };
+
+        assert_eq!(call.call_target(), Some(Address::from(0x69)));
+        assert_eq!(Transaction::default().call_target(), None);
+    }
+
+    #[test]
+    fn should_reject_sender_with_code() {
+        let t = Transaction::default().fake_sign(Address::from(0x69));
+
+        assert_eq!(t.check_sender_has_no_code(false), Ok(()));
+        assert_eq!(
+            t.check_sender_has_no_code(true),
+            Err(error::Error::SenderIsContract)
+        );
+    }
+
+    #[test]
+    fn should_round_trip_compact_bytes() {
+        use ethkey::{Generator, Random};
+
+        let key = Random.generate().unwrap();
+        let t = Transaction {
+            data: b"Hello!".to_vec(),
+            ..Default::default()
+        }
+        .sign(&key.secret(), Some(69));
+
+        let bytes = t.to_compact_bytes();
+        assert_eq!(bytes[0], 1);
+        let (restored, sender) = UnverifiedTransaction::from_compact_bytes(&bytes).unwrap();
+        assert_eq!(sender, key.address());
+        assert_eq!(&restored, &*t);
+        assert_eq!(SignedTransaction::new(restored).unwrap().sender(), sender);
+
+        let mut tampered = bytes.clone();
+        tampered[1..21].copy_from_slice(&Address::from(0x69));
+        let (restored, sender) = UnverifiedTransaction::from_compact_bytes(&tampered).unwrap();
+        assert_eq!(sender, Address::from(0x69));
+        assert_eq!(
+            SignedTransaction::new(restored).unwrap().sender(),
+            key.address()
+        );
+
+        let mut unknown_version = bytes.clone();
+        unknown_version[0] = 2;
+        assert!(UnverifiedTransaction::from_compact_bytes(&unknown_version).is_err());
+        assert!(UnverifiedTransaction::from_compact_bytes(&bytes[..10]).is_err());
+        assert!(UnverifiedTransaction::from_compact_bytes(&[]).is_err());
+    }
+
+    #[test]
+    fn should_restore_compact_bytes_without_recovery() {
+        use ethkey::{Generator, Random};
+
+        let key = Random.generate().unwrap();
+        let t = Transaction::default().sign(&key.secret(), Some(69));
+        let bytes = t.to_compact_bytes();
+
+        let restored = SignedTransaction::from_compact_bytes_unchecked(&bytes).unwrap();
+        assert_eq!(restored.sender(), key.address());
+        assert_eq!(restored.public_key(), None);
+        assert_eq!(&*restored, &*t);
+
+        // The stored sender is trusted as is.
+        let mut tampered = bytes.clone();
+        tampered[1..21].copy_from_slice(&Address::from(0x69));
+        let restored = SignedTransaction::from_compact_bytes_unchecked(&tampered).unwrap();
+        assert_eq!(restored.sender(), Address::from(0x69));
+        assert!(SignedTransaction::from_compact_bytes_unchecked(&bytes[..10]).is_err());
+    }
+
+    #[test]
+    fn should_compute_chain_independent_content_hash() {
+        use ethkey::{Generator, Random};
+
+        let key = Random.generate().unwrap();
+        let template = Transaction {
+            action: Action::Call(Address::from(0x69)),
+            value: U256::from(1),
+            ..Default::default()
+        };
+        let on_chain = |chain_id: u64| template.clone().sign(&key.secret(), Some(chain_id));
+
+        let (a, b) = (on_chain(1), on_chain(69));
+        assert_ne!(a.hash(), b.hash());
+        assert_ne!(
+            a.as_unsigned().hash(Some(1)),
+            b.as_unsigned().hash(Some(69))
+        );
+        assert_eq!(a.content_hash(), b.content_hash());
+        assert_eq!(a.content_hash(), template.content_hash());
+    }
+
+    #[test]
+    fn should_compute_min_replacement_price() {
+        let t = |gas_price: U256| Transaction {
+            gas_price,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            t(U256::from(20_000_000_000u64)).min_replacement_price(10),
+            U256::from(22_000_000_000u64)
+        );
+        assert_eq!(t(U256::from(20)).min_replacement_price(0), U256::from(20));
+        assert_eq!(
+            t(U256::max_value()).min_replacement_price(10),
+            U256::max_value()
+        );
+    }
+
+    #[test]
+    fn should_clamp_gas_price() {
+        let clamped = |gas_price: u64| {
+            let mut t = Transaction {
+                gas_price: U256::from(gas_price),
+                ..Default::default()
+            };
+            t.clamp_gas_price(U256::from(10), U256::from(100));
+            t.gas_price
+        };
+
+        assert_eq!(clamped(5), U256::from(10));
+        assert_eq!(clamped(50), U256::from(50));
+        assert_eq!(clamped(500), U256::from(100));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn should_not_clamp_gas_price_into_empty_range() {
+        Transaction::default().clamp_gas_price(U256::from(100), U256::from(10));
+    }
+
+    #[test]
+    fn should_detect_cross_chain_replayable_transactions() {
+        use ethkey::{Generator, Random};
+
+        let pre_eip155 = UnverifiedTransaction::from_hex("0xf85f800182520894095e7baea6a6c7c4c2dfeb977efac326af552d870a801ba048b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353a0efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804").unwrap();
+        assert!(is_cross_chain_replayable(&pre_eip155));
+
+        let key = Random.generate().unwrap();
+        let post_eip155 = Transaction::default().sign(&key.secret(), Some(1));
+        assert!(!is_cross_chain_replayable(&post_eip155));
+    }
+
+    #[test]
+    fn should_return_chain_id_bytes() {
+        use ethkey::{Generator, Random};
+
+        let key = Random.generate().unwrap();
+        let on_chain = |chain_id: u64| Transaction::default().sign(&key.secret(), Some(chain_id));
+
+        assert_eq!(on_chain(1).chain_id_bytes(), Some(vec![0x01]));
+        assert_eq!(on_chain(1337).chain_id_bytes(), Some(vec![0x05, 0x39]));
+        assert_eq!(
+            Transaction::default()
+                .sign(&key.secret(), None)
+                .chain_id_bytes(),
+            None
+        );
+    }
+
+    #[test]
+    fn should_summarize_transaction() {
+        let t = UnverifiedTransaction::from_hex("0xf85f800182520894095e7baea6a6c7c4c2dfeb977efac326af552d870a801ba048b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353a0efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804").unwrap();
+        let t = SignedTransaction::new(t).unwrap();
+
+        assert_eq!(
+            t.summary(),
+            TransactionSummary {
+                hash: "41df922fd0d4766fcc02e161f8295ec28522f329ae487f14d811e4b64c8d6e31".into(),
+                tx_type: 0,
+                from: Some("0f65fe9276bc9a24ae7083ae28e2660ef72df99e".into()),
+                to: Some("095e7baea6a6c7c4c2dfeb977efac326af552d87".into()),
+                nonce: U256::zero(),
+                value: U256::from(0x0au64),
+                gas: U256::from(0x5208u64),
+            }
+        );
+    }
+
+    #[test]
+    fn should_recover_pre_eip155_transaction_from_six_field_pre_image() {
+        let t = UnverifiedTransaction::from_hex("0xf85f800182520894095e7baea6a6c7c4c2dfeb977efac326af552d870a801ba048b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353a0efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804").unwrap();
+        assert_eq!(t.original_v(), 27);
+        assert_eq!(t.standard_v(), 0);
+        assert_eq!(t.chain_id(), None);
+
+        let mut pre_image = RlpStream::new_list(6);
+        pre_image
+            .append(&t.nonce)
+            .append(&t.gas_price)
+            .append(&t.gas)
+            .append(&t.action)
+            .append(&t.value)
+            .append(&t.data);
+        assert_eq!(t.as_unsigned().hash(None), keccak(pre_image.as_raw()));
+
+        let expected: Address = "0f65fe9276bc9a24ae7083ae28e2660ef72df99e".into();
+        assert_eq!(public_to_address(&t.recover_public().unwrap()), expected);
+        let from_nine_fields = recover(&t.signature(), &t.as_unsigned().hash(Some(0)))
+            .map(|public| public_to_address(&public));
+        assert_ne!(from_nine_fields, Ok(expected));
+
+        let with_v_28 = UnverifiedTransaction {
+            signature: SignatureComponents {
+                v: 28,
+                ..t.signature.clone()
+            },
+            ..t
+        };
+        assert_eq!(with_v_28.standard_v(), 1);
+        assert_eq!(with_v_28.chain_id(), None);
+    }
+
+    #[test]
+    fn should_score_higher_fee_above_lower_fee_for_gossip() {
+        let t = |gas_price: u64| {
+            Transaction {
+                gas_price: U256::from(gas_price),
+                data: vec![0x11; 100],
+                ..Default::default()
+            }
+            .fake_sign(Address::from(0x69))
+        };
+        let (cheap, pricey) = (t(1_000_000), t(2_000_000));
+        assert_eq!(cheap.rlp_bytes().len(), pricey.rlp_bytes().len());
+
+        assert!(pricey.gossip_score(None) > cheap.gossip_score(None));
+        let base_fee = Some(U256::from(500_000));
+        assert!(pricey.gossip_score(base_fee) > cheap.gossip_score(base_fee));
+        assert_eq!(t(400_000).gossip_score(base_fee), 0);
+
+        let huge = Transaction {
+            gas_price: U256::max_value(),
+            ..Default::default()
+        }
+        .fake_sign(Address::from(0x69));
+        assert_eq!(huge.gossip_score(None), u64::max_value());
+    }
+
+    #[test]
+    fn should_reject_zero_gas_limit() {
+        let t = Transaction::default();
+        assert_eq!(t.check_nonzero_gas(), Err(error::Error::ZeroGasLimit));
+
+        let t = Transaction {
+            gas: U256::from(21_000),
+            ..Default::default()
+        };
+        assert_eq!(t.check_nonzero_gas(), Ok(()));
+    }
+
+    #[test]
+    fn should_estimate_blocks_until_condition_is_ready() {
+        let number = Condition::Number(110);
+        assert_eq!(number.blocks_until_ready(100, 0, 15), 10);
+        assert_eq!(number.blocks_until_ready(110, 0, 15), 0);
+        assert_eq!(number.blocks_until_ready(120, 0, 15), 0);
+
+        let timestamp = Condition::Timestamp(1_000);
+        assert_eq!(timestamp.blocks_until_ready(0, 850, 15), 10);
+        assert_eq!(timestamp.blocks_until_ready(0, 851, 15), 10);
+        assert_eq!(timestamp.blocks_until_ready(0, 986, 15), 1);
+        assert_eq!(timestamp.blocks_until_ready(0, 1_000, 15), 0);
+        assert_eq!(timestamp.blocks_until_ready(0, 2_000, 15), 0);
+        assert_eq!(timestamp.blocks_until_ready(0, 990, 0), 10);
+    }
+
+    #[test]
+    fn should_detect_same_transaction_despite_malleated_signature() {
+        let raw: Vec<u8> = "f85f800182520894095e7baea6a6c7c4c2dfeb977efac326af552d870a801ba048b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353a0efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804".from_hex().unwrap();
+        let t: UnverifiedTransaction = rlp::decode(&raw).unwrap();
+        assert_eq!(same_transaction(&raw, &raw), Ok(true));
+
+        let malleated = UnverifiedTransaction {
+            signature: SignatureComponents {
+                v: 28,
+                r: t.signature.r,
+                s: ethkey::math::curve_order() - t.signature.s,
+            },
+            ..t.clone()
+        };
+        let malleated_raw = rlp::encode(&malleated).to_vec();
+        assert_ne!(raw, malleated_raw);
+        assert_eq!(
+            malleated.recover_public().unwrap(),
+            t.recover_public().unwrap()
+        );
+        assert_eq!(same_transaction(&raw, &malleated_raw), Ok(true));
+        assert_eq!(same_transaction(&malleated_raw, &raw), Ok(true));
+
+        let different = UnverifiedTransaction {
+            unsigned: Transaction {
+                nonce: U256::from(1),
+                ..t.unsigned.clone()
+            },
+            ..t.clone()
+        };
+        assert_eq!(
+            same_transaction(&raw, &rlp::encode(&different).to_vec()),
+            Ok(false)
+        );
+        assert!(same_transaction(&raw, &[0xc0]).is_err());
+    }
+
+    #[test]
+    fn should_reject_trailing_bytes_in_exact_decoding() {
+        let raw: Vec<u8> = "f85f800182520894095e7baea6a6c7c4c2dfeb977efac326af552d870a801ba048b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353a0efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804".from_hex().unwrap();
+        let t = UnverifiedTransaction::decode_exact(&raw).unwrap();
+        assert_eq!(t, rlp::decode(&raw).unwrap());
+
+        let mut with_junk = raw.clone();
+        with_junk.extend_from_slice(&[0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(
+            UnverifiedTransaction::decode_exact(&with_junk),
+            Err(DecoderError::RlpIsTooBig)
+        );
+        assert!(UnverifiedTransaction::decode_exact(&raw[..raw.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn should_compute_overpayment_versus_target_price() {
+        let t = Transaction {
+            gas_price: U256::from(30),
+            gas: U256::from(21_000),
+            ..Default::default()
+        };
+        assert_eq!(t.overpayment(U256::from(20)), U256::from(210_000));
+        assert_eq!(t.overpayment(U256::from(30)), U256::zero());
+        assert_eq!(t.overpayment(U256::from(40)), U256::zero());
+    }
+
+    #[test]
+    fn should_detect_deterministic_nonce() {
+        use ethkey::{Generator, Random};
+
+        let key = Random.generate().unwrap();
+        let t = Transaction {
+            nonce: U256::from(42),
+            gas: U256::from(21_000),
+            ..Default::default()
+        }
+        .sign(&key.secret(), Some(1));
+        assert!(t.uses_deterministic_nonce(&key.secret()));
+
+        let other = Random.generate().unwrap();
+        assert!(!t.uses_deterministic_nonce(&other.secret()));
+
+        let malleated = UnverifiedTransaction {
+            signature: SignatureComponents {
+                v: if t.standard_v() == 0 {
+                    t.signature.v + 1
+                } else {
+                    t.signature.v - 1
+                },
+                r: t.signature.r,
+                s: ethkey::math::curve_order() - t.signature.s,
+            },
+            ..(*t).clone()
+        };
+        assert_eq!(malleated.chain_id(), Some(1));
+        assert_eq!(
+            malleated.recover_public().unwrap(),
+            t.recover_public().unwrap()
+        );
+        assert!(!malleated.uses_deterministic_nonce(&key.secret()));
+    }
+
+    #[test]
+    fn should_detect_precompile_targets() {
+        let call = |to: Address| Transaction {
+            action: Action::Call(to),
+            ..Default::default()
+        };
+        assert!(call(Address::from(0x01)).targets_precompile(9));
+        assert!(call(Address::from(0x09)).targets_precompile(9));
+        assert!(!call(Address::from(0x09)).targets_precompile(8));
+        assert!(!call(Address::from(0x00)).targets_precompile(9));
+        assert!(!call("095e7baea6a6c7c4c2dfeb977efac326af552d87".into()).targets_precompile(9));
+        assert!(!Transaction::default().targets_precompile(9));
+    }
+
+    #[test]
+    fn should_itemize_intrinsic_gas() {
+        let t = Transaction {
+            action: Action::Create,
+            data: vec![0x60, 0x00, 0x60, 0x00, 0xf3],
+            ..Default::default()
+        };
+        let schedule = GasSchedule::istanbul();
+        let breakdown = t.gas_breakdown(&schedule);
+
+        assert_eq!(
+            breakdown,
+            GasBreakdown {
+                base: 21000,
+                create: 32000,
+                calldata_zero: 2 * 4,
+                calldata_nonzero: 3 * 16,
+            }
+        );
+        assert_eq!(breakdown.total(), t.intrinsic_gas(&schedule));
+        assert_eq!(
+            breakdown.calldata_zero + breakdown.calldata_nonzero,
+            t.calldata_gas(4, 16)
+        );
+    }
+
+    #[test]
+    fn should_check_tx_gas_cap() {
+        let t = Transaction {
+            gas: U256::from(1_000_000),
+            ..Default::default()
+        };
+        assert_eq!(t.check_tx_gas_cap(U256::from(1_000_000)), Ok(()));
+        assert_eq!(
+            t.check_tx_gas_cap(U256::from(999_999)),
+            Err(error::Error::TxGasCapExceeded {
+                got: U256::from(1_000_000),
+                cap: U256::from(999_999),
+            })
+        );
+    }
+
+    #[test]
+    fn should_pre_check_recoverability() {
+        let t = UnverifiedTransaction::from_hex("0xf85f800182520894095e7baea6a6c7c4c2dfeb977efac326af552d870a801ba048b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353a0efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804").unwrap();
+        assert!(t.is_recoverable());
+
+        let with_signature = |v: u64, r: U256, s: U256| UnverifiedTransaction {
+            signature: SignatureComponents { v, r, s },
+            ..t.clone()
+        };
+        let (r, s) = (t.signature.r, t.signature.s);
+        assert!(with_signature(37, r, s).is_recoverable());
+        assert!(!with_signature(29, r, s).is_recoverable());
+        assert!(!with_signature(27, U256::zero(), U256::zero()).is_recoverable());
+        assert!(!with_signature(27, U256::zero(), s).is_recoverable());
+        assert!(!with_signature(27, r, ethkey::math::curve_order()).is_recoverable());
+        assert!(!Transaction::default().null_sign(1).is_recoverable());
+    }
+
+    #[test]
+    fn should_compute_data_entropy() {
+        let with_data = |data: Vec<u8>| Transaction {
+            data,
+            ..Default::default()
+        };
+        assert_eq!(with_data(vec![]).data_entropy(), 0.0);
+        assert_eq!(with_data(vec![0; 1024]).data_entropy(), 0.0);
+
+        let all_bytes: Vec<u8> = (0..1024).map(|i| (i % 256) as u8).collect();
+        assert!((with_data(all_bytes).data_entropy() - 8.0).abs() < 1e-9);
+
+        let random: Vec<u8> = (0..32).flat_map(|_| H256::random().to_vec()).collect();
+        let entropy = with_data(random).data_entropy();
+        assert!(entropy > 7.0 && entropy <= 8.0);
+    }
+
+    #[test]
+    fn should_check_nonce_fits_in_u64() {
+        let with_nonce = |nonce: U256| Transaction {
+            nonce,
+            ..Default::default()
+        };
+        assert_eq!(with_nonce(U256::from(42)).check_nonce_u64(), Ok(42));
+        assert_eq!(
+            with_nonce(U256::from(u64::max_value())).check_nonce_u64(),
+            Ok(u64::max_value())
+        );
+        let oversized = U256::from(u64::max_value()) + U256::one();
+        assert_eq!(
+            with_nonce(oversized).check_nonce_u64(),
+            Err(error::Error::NonceOverflow(oversized))
+        );
+    }
+
+    #[test]
+    fn should_round_trip_through_proto_bytes() {
+        use ethkey::{Generator, Random};
+
+        let key = Random.generate().unwrap();
+        let call = Transaction {
+            nonce: U256::from(300),
+            gas_price: U256::from(20_000_000_000u64),
+            gas: U256::from(21_000),
+            action: Action::Call(Address::from(0x1234)),
+            value: U256::max_value(),
+            data: vec![0x00, 0x01, 0xff],
+        }
+        .sign(&key.secret(), Some(1));
+        let create = Transaction {
+            gas: U256::from(100_000),
+            data: vec![0x60; 200],
+            ..Default::default()
+        }
+        .sign(&key.secret(), None);
+        let unsigned = Transaction::default().null_sign(1);
+
+        for tx in &[call, create, unsigned] {
+            let bytes = tx.to_proto_bytes();
+            let decoded = UnverifiedTransaction::from_proto_bytes(&bytes).unwrap();
+            assert_eq!(&decoded, &**tx);
+            assert_eq!(decoded.hash(), tx.hash());
+        }
+
+        let t = Transaction::default().null_sign(1);
+        let mut bytes = t.to_proto_bytes();
+        bytes[1] = 2;
+        assert_eq!(
+            UnverifiedTransaction::from_proto_bytes(&bytes),
+            Err(DecoderError::Custom(
+                "Unknown proto transaction format version"
+            ))
+        );
+        let bytes = t.to_proto_bytes();
+        assert!(UnverifiedTransaction::from_proto_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert_eq!(
+            UnverifiedTransaction::from_proto_bytes(&bytes[2..]),
+            Err(DecoderError::Custom("Missing proto field: version"))
+        );
+    }
+
+    #[test]
+    fn should_detect_underpriced_transaction() {
+        let t = Transaction {
+            gas_price: U256::from(100),
+            ..Default::default()
+        };
+        assert!(!t.is_underpriced(U256::from(99)));
+        assert!(!t.is_underpriced(U256::from(100)));
+        assert!(t.is_underpriced(U256::from(101)));
+    }
+
+    #[test]
+    fn should_include_sender_in_recovery_candidates() {
+        let t = UnverifiedTransaction::from_hex("0xf85f800182520894095e7baea6a6c7c4c2dfeb977efac326af552d870a801ba048b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353a0efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804").unwrap();
+        let sender: Address = "0f65fe9276bc9a24ae7083ae28e2660ef72df99e".into();
+
+        let candidates = t.recovery_candidates();
+        assert!(candidates.contains(&sender));
+        assert!(candidates.len() <= 2);
+
+        let corrupt = UnverifiedTransaction {
+            signature: SignatureComponents {
+                v: 29,
+                ..t.signature.clone()
+            },
+            ..t.clone()
+        };
+        assert!(corrupt.recover_public().is_err());
+        assert!(corrupt.recovery_candidates().contains(&sender));
+    }
+
+    #[test]
+    fn should_compute_hashes_in_order() {
+        let txs: Vec<UnverifiedTransaction> = (0..5)
+            .map(|nonce| {
+                Transaction {
+                    nonce: U256::from(nonce as u64),
+                    ..Default::default()
+                }
+                .null_sign(1)
+                .deconstruct()
+                .0
+            })
+            .collect();
+
+        let hashes = compute_hashes(&txs);
+        let expected: Vec<H256> = txs.iter().map(|tx| keccak(&*tx.rlp_bytes())).collect();
+        assert_eq!(hashes, expected);
+        assert_eq!(hashes, txs.iter().map(|tx| tx.hash()).collect::<Vec<_>>());
+        assert!(compute_hashes(&[]).is_empty());
+    }
+
+    #[test]
+    fn should_match_method_selector() {
+        let call = |data: Vec<u8>| Transaction {
+            action: Action::Call(Address::from(0x1234)),
+            data,
+            ..Default::default()
+        };
+        let data = vec![0xa9, 0x05, 0x9c, 0xbb, 0x00, 0x01];
+        assert!(call(data.clone()).matches_selector(ERC20_TRANSFER_SELECTOR));
+        assert!(call(data[..4].to_vec()).matches_selector(ERC20_TRANSFER_SELECTOR));
+        assert!(!call(data.clone()).matches_selector([0x09, 0x5e, 0xa7, 0xb3]));
+        assert!(!call(data[..3].to_vec()).matches_selector(ERC20_TRANSFER_SELECTOR));
+
+        let create = Transaction {
+            data,
+            ..Default::default()
+        };
+        assert!(!create.matches_selector(ERC20_TRANSFER_SELECTOR));
+    }
+
+    #[test]
+    fn should_hash_unsigned_rlp_for_signing() {
+        let t = Transaction {
+            nonce: U256::from(9),
+            gas_price: U256::from(20_000_000_000u64),
+            gas: U256::from(21_000),
+            action: Action::Call("3535353535353535353535353535353535353535".into()),
+            value: U256::from(1_000_000_000_000_000_000u64),
+            data: vec![],
+        };
+        // EIP-155 example signing data
+        assert_eq!(
+            t.rlp_unsigned(Some(1)),
+            "ec098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a764000080018080".from_hex().unwrap()
+        );
+        for chain_id in &[None, Some(1)] {
+            assert_eq!(keccak(t.rlp_unsigned(*chain_id)), t.hash(*chain_id));
+        }
+        assert_ne!(t.rlp_unsigned(None), t.rlp_unsigned(Some(1)));
+    }
+
+    #[test]
+    fn should_detect_funded_create() {
+        let t = |action: Action, value: u64| Transaction {
+            action,
+            value: U256::from(value),
+            ..Default::default()
+        };
+        assert!(t(Action::Create, 1).is_funded_create());
+        assert!(!t(Action::Create, 0).is_funded_create());
+        assert!(!t(Action::Call(Address::from(0x1234)), 1).is_funded_create());
+    }
+
+    #[test]
+    fn should_peek_transaction_type() {
+        let legacy = rlp::encode(&Transaction::default().null_sign(1)).to_vec();
+        assert_eq!(peek_tx_type(&legacy), Ok(0));
+
+        let mut access_list = vec![0x01];
+        access_list.extend_from_slice(&legacy);
+        assert_eq!(peek_tx_type(&access_list), Ok(1));
+
+        let mut fee_market = vec![0x02];
+        fee_market.extend_from_slice(&legacy);
+        assert_eq!(peek_tx_type(&fee_market), Ok(2));
+
+        assert_eq!(peek_tx_type(&[]), Err(DecoderError::RlpIsTooShort));
+        assert_eq!(
+            peek_tx_type(&[0x82, 0x01, 0x02]),
+            Err(DecoderError::RlpExpectedToBeList)
+        );
+    }
+
+    #[test]
+    fn should_sort_localized_transactions_chronologically() {
+        let localized =
+            |block_number: BlockNumber, transaction_index: usize| LocalizedTransaction {
+                signed: Transaction::default().null_sign(1).deconstruct().0,
+                block_number,
+                block_hash: H256::from(block_number),
+                transaction_index,
+                cached_sender: None,
+            };
+        let t = localized(10, 3);
+        assert_eq!(t.sort_key(), (t.block_number, t.transaction_index));
+
+        let mut txs = vec![localized(11, 0), localized(10, 3), localized(10, 1)];
+        txs.sort_by_key(LocalizedTransaction::sort_key);
+        let order: Vec<_> = txs.iter().map(LocalizedTransaction::sort_key).collect();
+        assert_eq!(order, vec![(10, 1), (10, 3), (11, 0)]);
+    }
+
+    #[test]
+    fn should_find_gas_price_bucket() {
+        let buckets: Vec<U256> = [1u64, 10, 100, 1000]
+            .iter()
+            .map(|b| U256::from(*b))
+            .collect();
+        let bucket = |gas_price: u64| {
+            Transaction {
+                gas_price: U256::from(gas_price),
+                ..Default::default()
+            }
+            .gas_price_bucket(None, &buckets)
+        };
+        assert_eq!(bucket(0), None);
+        assert_eq!(bucket(1), Some(0));
+        assert_eq!(bucket(9), Some(0));
+        assert_eq!(bucket(10), Some(1));
+        assert_eq!(bucket(999), Some(2));
+        assert_eq!(bucket(1000), Some(3));
+        assert_eq!(bucket(u64::max_value()), Some(3));
+        assert_eq!(Transaction::default().gas_price_bucket(None, &[]), None);
+        let t = Transaction {
+            gas_price: U256::from(100),
+            ..Default::default()
+        };
+        assert_eq!(t.gas_price_bucket(Some(U256::from(50)), &buckets), Some(2));
+    }
 }
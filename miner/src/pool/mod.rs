@@ -36,6 +36,7 @@ mod tests;
 
 pub use self::{
     queue::{Status as QueueStatus, TransactionQueue},
+    ready::partition_ready,
     txpool::{Options, VerifiedTransaction as PoolVerifiedTransaction},
 };
 
@@ -96,6 +96,48 @@ impl<C: NonceClient> txpool::Ready<VerifiedTransaction> for State<C> {
     }
 }
 
+/// Splits transactions by readiness against the given account nonces (zero for unknown senders),
+/// like `State` does for the pool, and returns `(ready, future, stale)`.
+///
+/// - ready: the nonce is the next nonce of the sender or directly follows another ready
+///   transaction of the same sender,
+/// - future: the nonce leaves a gap,
+/// - stale: the nonce is already used, by the account or by a ready transaction.
+///
+/// Each of the returned lists is ordered by sender and then by nonce.
+pub fn partition_ready(
+    mut txs: Vec<transaction::SignedTransaction>,
+    account_nonces: &HashMap<Address, U256>,
+) -> (
+    Vec<transaction::SignedTransaction>,
+    Vec<transaction::SignedTransaction>,
+    Vec<transaction::SignedTransaction>,
+) {
+    txs.sort_by_key(|tx| (tx.sender(), tx.nonce));
+
+    let mut ready = Vec::new();
+    let mut future = Vec::new();
+    let mut stale = Vec::new();
+    let mut next: Option<(Address, U256)> = None;
+    for tx in txs {
+        let sender = tx.sender();
+        let nonce = match next {
+            Some((address, nonce)) if address == sender => nonce,
+            _ => account_nonces.get(&sender).cloned().unwrap_or_default(),
+        };
+        next = Some((sender, nonce));
+        match tx.nonce.cmp(&nonce) {
+            cmp::Ordering::Greater => future.push(tx),
+            cmp::Ordering::Less => stale.push(tx),
+            cmp::Ordering::Equal => {
+                next = Some((sender, nonce.saturating_add(U256::from(1))));
+                ready.push(tx);
+            }
+        }
+    }
+    (ready, future, stale)
+}
+
 /// Checks readines of Pending transactions by comparing it with current time and block number.
 #[derive(Debug)]
 pub struct Condition {
@@ -270,4 +312,52 @@ mod tests {
             txpool::Readiness::Ready
         );
     }
+
+    #[test]
+    fn should_partition_transactions_by_readiness() {
+        // given
+        let tx = |nonce: u64, sender: u64| {
+            transaction::Transaction {
+                nonce: nonce.into(),
+                ..Default::default()
+            }
+            .fake_sign(sender.into())
+        };
+        let nonces = |txs: &[transaction::SignedTransaction]| -> Vec<(Address, U256)> {
+            txs.iter().map(|tx| (tx.sender(), tx.nonce)).collect()
+        };
+        let mut account_nonces = HashMap::new();
+        account_nonces.insert(1.into(), 5.into());
+        let txs = vec![
+            tx(7, 1),
+            tx(5, 1),
+            tx(6, 1),
+            tx(9, 1),
+            tx(4, 1),
+            tx(0, 2),
+            tx(2, 2),
+        ];
+
+        // when
+        let (ready, future, stale) = partition_ready(txs, &account_nonces);
+
+        // then
+        assert_eq!(
+            nonces(&ready),
+            vec![
+                (Address::from(1), U256::from(5)),
+                (Address::from(1), U256::from(6)),
+                (Address::from(1), U256::from(7)),
+                (Address::from(2), U256::from(0)),
+            ]
+        );
+        assert_eq!(
+            nonces(&future),
+            vec![
+                (Address::from(1), U256::from(9)),
+                (Address::from(2), U256::from(2))
+            ]
+        );
+        assert_eq!(nonces(&stale), vec![(Address::from(1), U256::from(4))]);
+    }
 }
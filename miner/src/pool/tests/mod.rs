@@ -764,6 +764,83 @@ fn should_reject_transactions_below_base_gas() {
     );
 }
 
+#[test]
+fn should_reject_transactions_above_tx_gas_cap() {
+    // given
+    let txq = new_queue();
+    let tx = Tx {
+        gas: 1_000_001,
+        ..Default::default()
+    }
+    .signed();
+
+    // when
+    let res = txq.import(TestClient::new(), vec![tx].local());
+
+    // then
+    assert_eq!(
+        res,
+        vec![Err(transaction::Error::TxGasCapExceeded {
+            got: 1_000_001.into(),
+            cap: 1_000_000.into(),
+        })]
+    );
+}
+
+#[test]
+fn should_reject_transactions_above_block_gas_limit_but_under_tx_gas_cap() {
+    // given
+    let txq = TransactionQueue::new(
+        txpool::Options {
+            max_count: 3,
+            max_per_sender: 3,
+            max_mem_usage: TEST_QUEUE_MAX_MEM,
+        },
+        verifier::Options {
+            minimal_gas_price: 1.into(),
+            block_gas_limit: 500_000.into(),
+            tx_gas_limit: 1_000_000.into(),
+            no_early_reject: false,
+        },
+        PrioritizationStrategy::GasPriceOnly,
+    );
+    let tx = Tx {
+        gas: 500_001,
+        ..Default::default()
+    }
+    .signed();
+
+    // when
+    let res = txq.import(TestClient::new(), vec![tx].local());
+
+    // then
+    assert_eq!(
+        res,
+        vec![Err(transaction::Error::GasLimitExceeded {
+            limit: 500_000.into(),
+            got: 500_001.into(),
+        })]
+    );
+}
+
+#[test]
+fn should_reject_transactions_with_zero_gas() {
+    // given
+    let txq = new_queue();
+    let tx = Tx {
+        gas: 0,
+        ..Default::default()
+    }
+    .signed();
+
+    // when
+    let res = txq.import(TestClient::new(), vec![tx].local());
+
+    // then
+    // checked before the required gas, which would give `InsufficientGas`
+    assert_eq!(res, vec![Err(transaction::Error::ZeroGasLimit)]);
+}
+
 #[test]
 fn should_remove_out_of_date_transactions_occupying_queue() {
     // given
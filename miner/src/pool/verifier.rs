@@ -22,12 +22,9 @@
 //! May have some overlap with `Readiness` since we don't want to keep around
 //! stalled transactions.
 
-use std::{
-    cmp,
-    sync::{
-        atomic::{self, AtomicUsize},
-        Arc,
-    },
+use std::sync::{
+    atomic::{self, AtomicUsize},
+    Arc,
 };
 
 use ethereum_types::{H256, U256};
@@ -182,20 +179,34 @@ impl<C: Client> txpool::Verifier<Transaction>
             bail!(transaction::Error::AlreadyImported)
         }
 
-        let gas_limit = cmp::min(self.options.tx_gas_limit, self.options.block_gas_limit);
-        if tx.gas() > &gas_limit {
+        if let Err(err) = tx.transaction().check_nonzero_gas() {
+            trace!(target: "txqueue", "[{:?}] Rejected transaction with zero gas", hash);
+            bail!(err)
+        }
+
+        if let Err(err) = tx.transaction().check_tx_gas_cap(self.options.tx_gas_limit) {
             debug!(
                 target: "txqueue",
-                "[{:?}] Rejected transaction above gas limit: {} > min({}, {})",
+                "[{:?}] Rejected transaction above per-transaction gas cap: {} > {}",
                 hash,
                 tx.gas(),
-                self.options.block_gas_limit,
                 self.options.tx_gas_limit,
             );
-            bail!(transaction::Error::GasLimitExceeded {
-                limit: gas_limit,
-                got: *tx.gas(),
-            });
+            bail!(err)
+        }
+
+        if let Err(err) = tx
+            .transaction()
+            .check_within_block_gas_limit(self.options.block_gas_limit)
+        {
+            debug!(
+                target: "txqueue",
+                "[{:?}] Rejected transaction above block gas limit: {} > {}",
+                hash,
+                tx.gas(),
+                self.options.block_gas_limit,
+            );
+            bail!(err)
         }
 
         let minimal_gas = self.client.required_gas(tx.transaction());
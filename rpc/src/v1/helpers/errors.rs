@@ -370,6 +370,12 @@ pub fn transaction_message(error: &TransactionError) -> String {
     match *error {
 		AlreadyImported => "Transaction with the same hash was already imported.".into(),
 		Old => "Transaction nonce is too low. Try incrementing the nonce.".into(),
+		NonceTooFarInFuture { limit, got } => {
+			format!("Transaction nonce is too far in the future (limit: {}, got: {}). Try decreasing the nonce.", limit, got)
+		}
+		NonceOverflow(nonce) => {
+			format!("Transaction nonce {} is too large, it must fit in 64 bits.", nonce)
+		}
 		TooCheapToReplace { prev, new } => {
 			format!("Transaction gas price {} is too low. There is another transaction with same nonce in the queue{}. Try increasing the gas price or incrementing the nonce.",
 					new.map(|gas| format!("{}wei", gas)).unwrap_or("supplied".into()),
@@ -391,15 +397,27 @@ pub fn transaction_message(error: &TransactionError) -> String {
 		GasLimitExceeded { limit, got } => {
 			format!("Transaction cost exceeds current gas limit. Limit: {}, got: {}. Try decreasing supplied gas.", limit, got)
 		}
+		TxGasCapExceeded { got, cap } => {
+			format!("Transaction gas exceeds the per-transaction gas cap. Cap: {}, got: {}. Try decreasing supplied gas.", cap, got)
+		}
+		GasPriceTooHigh { limit, got } => {
+			format!("Transaction gas price is too high. It exceeds the configured limit (limit: {}, got: {}). Try decreasing the gas price.", limit, got)
+		}
 		InvalidSignature(ref sig) => format!("Invalid signature: {}", sig),
 		InvalidChainId => "Invalid chain id.".into(),
+		ZeroGasLimit => "Transaction gas is zero. Try increasing supplied gas.".into(),
 		InvalidGasLimit(_) => "Supplied gas is beyond limit.".into(),
 		SenderBanned => "Sender is banned in local queue.".into(),
+		SenderIsContract => "Transaction sender has deployed code and cannot send transactions.".into(),
 		RecipientBanned => "Recipient is banned in local queue.".into(),
 		CodeBanned => "Code is banned in local queue.".into(),
 		NotAllowed => "Transaction is not permitted.".into(),
 		TooBig => "Transaction is too big, see chain specification for the limit.".into(),
 		InvalidRlp(ref descr) => format!("Invalid RLP data: {}", descr),
+		SigningHashMismatch { expected, computed } => {
+			format!("Signature does not match the transaction. Signed hash: {:?}, transaction hash: {:?}.", expected, computed)
+		}
+		Rejected(ref reason) => format!("{}.", reason),
 	}
 }
 